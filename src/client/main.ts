@@ -11,6 +11,7 @@ import {
   sendAndConfirmTransaction,
   TransactionInstruction,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
   SystemInstruction,
 } from "@solana/web3.js";
 import * as SPLToken from "@solana/spl-token";
@@ -39,13 +40,22 @@ async function main() {
   console.log("Program ID:", QFProgramID.toBase58());
   console.log("");
 
+  // init owner withdraw receiver, the fee recipient of the round
+  let { playerTokenHolderPubkey: roundOwnerWithdrawReceiverPubkey } = await InitPlayer(
+    connection,
+    feePayer,
+    await connection.getMinimumBalanceForRentExemption(SPLToken.AccountLayout.span)
+  );
+  console.log("=> Init Round Owner Token Receiver");
+  await printTokenAccount(connection, feePayer, roundOwnerWithdrawReceiverPubkey, "Round Owner Token Receiver");
+
   // start new round
   let {
     owner: roundOwner,
     roundPubkey,
     vaultPubkey,
     txHash: createNewRoundTxHash,
-  } = await CreateNewRound(connection, feePayer, QFProgramID);
+  } = await CreateNewRound(connection, feePayer, QFProgramID, roundOwnerWithdrawReceiverPubkey);
   console.log("=> Start New Round", createNewRoundTxHash);
   await printRoundInfo(connection, roundPubkey);
   console.log("vault owner", (await getVaultOwnerPubkey(roundPubkey, QFProgramID)).toBase58());
@@ -70,7 +80,7 @@ async function main() {
     txHash: registerProject1TxHash,
     projectPubkey: project1Pubkey,
     owner: project1Owner,
-  } = await RegisterProject(connection, feePayer, roundPubkey, QFProgramID);
+  } = await RegisterProject(connection, feePayer, roundPubkey, QFProgramID, "Project 1");
   console.log("=> Register Project 1", registerProject1TxHash);
   await printProjectInfo(connection, project1Pubkey);
 
@@ -79,7 +89,7 @@ async function main() {
     txHash: registerProject2TxHash,
     projectPubkey: project2Pubkey,
     owner: project2Owner,
-  } = await RegisterProject(connection, feePayer, roundPubkey, QFProgramID);
+  } = await RegisterProject(connection, feePayer, roundPubkey, QFProgramID, "Project 2");
   console.log("=> Register Project 2", registerProject2TxHash);
   await printProjectInfo(connection, project2Pubkey);
 
@@ -90,9 +100,8 @@ async function main() {
   let { txHash: initAliceProject1VoterTxHash, voterPubkey: AliceProject1VoterPubkey } = await InitVoter(
     connection,
     feePayer,
-    feePayer,
+    Alice,
     project1Pubkey,
-    AliceTokenHolderPubkey,
     QFProgramID
   );
   console.log("=> Init Alice Projcet 1 Voter", initAliceProject1VoterTxHash);
@@ -102,9 +111,8 @@ async function main() {
   let { txHash: initAliceProject2VoterTxHash, voterPubkey: AliceProject2VoterPubkey } = await InitVoter(
     connection,
     feePayer,
-    feePayer,
+    Alice,
     project2Pubkey,
-    AliceTokenHolderPubkey,
     QFProgramID
   );
   console.log("=> Init Alice Projcet 2 Voter", initAliceProject2VoterTxHash);
@@ -159,9 +167,8 @@ async function main() {
   let { txHash: initBobProject1Voter, voterPubkey: bobProject1VoterPubkey } = await InitVoter(
     connection,
     feePayer,
-    feePayer,
+    bob,
     project1Pubkey,
-    bobTokenHolderPubkey,
     QFProgramID
   );
   console.log("=> Init Bob Projcet 1 Voter", initBobProject1Voter);
@@ -232,15 +239,6 @@ async function main() {
   console.log("=> Init Project 2 Token Receiver");
   await printTokenAccount(connection, feePayer, project2WithdrawReceiverPubkey, "Project 2 Token Receiver");
 
-  // init owner withdraw receiver
-  let { playerTokenHolderPubkey: roundOwnerWithdrawReceiverPubkey } = await InitPlayer(
-    connection,
-    feePayer,
-    await connection.getMinimumBalanceForRentExemption(SPLToken.AccountLayout.span)
-  );
-  console.log("=> Init Round Owner Token Receiver");
-  await printTokenAccount(connection, feePayer, roundOwnerWithdrawReceiverPubkey, "Round Owner Token Receiver");
-
   let { txHash: project1WithdrawTxHash } = await Withdraw(
     connection,
     feePayer,
@@ -324,6 +322,22 @@ enum Instruction {
   WithdrawFee,
 }
 
+type RoundConfig = {
+  startTime: number; // i64
+  endTime: number; // i64
+  registrationEndTime: number; // i64
+  feeBps: number; // u16
+  feePolicy: number; // u8
+  matchingCapBps: number; // u16
+  matchingAlgorithm: number; // u8
+  pairwiseThreshold: number; // u64
+  identityIssuer: PublicKey;
+  curated: boolean;
+  emptyRoundPolicy: number; // u8
+  vestingCliff: number; // i64
+  vestingDuration: number; // i64
+};
+
 function createStartRoundInstruction(
   programId: PublicKey,
   newRoundPubkey: PublicKey,
@@ -331,14 +345,46 @@ function createStartRoundInstruction(
   funderPubkey: PublicKey,
   associatedTokenAccountPubkey: PublicKey,
   walletAccountPubkey: PublicKey,
-  mintPubkey: PublicKey
+  mintPubkey: PublicKey,
+  feeRecipientPubkey: PublicKey,
+  configPubkey: PublicKey,
+  creatorPubkey: PublicKey,
+  config: RoundConfig
 ): TransactionInstruction {
-  const dataLayout = BufferLayout.struct([BufferLayout.u8("instruction")]);
+  const dataLayout = BufferLayout.struct([
+    BufferLayout.u8("instruction"),
+    BufferLayout.blob(8, "startTime"),
+    BufferLayout.blob(8, "endTime"),
+    BufferLayout.blob(8, "registrationEndTime"),
+    BufferLayout.u16("feeBps"),
+    BufferLayout.u8("feePolicy"),
+    BufferLayout.u16("matchingCapBps"),
+    BufferLayout.u8("matchingAlgorithm"),
+    BufferLayout.blob(8, "pairwiseThreshold"),
+    BufferLayout.blob(32, "identityIssuer"),
+    BufferLayout.u8("curated"),
+    BufferLayout.u8("emptyRoundPolicy"),
+    BufferLayout.blob(8, "vestingCliff"),
+    BufferLayout.blob(8, "vestingDuration"),
+  ]);
 
   const data = Buffer.alloc(dataLayout.span);
   dataLayout.encode(
     {
       instruction: Instruction.StartRound,
+      startTime: new BN(config.startTime).toBuffer("le", 8),
+      endTime: new BN(config.endTime).toBuffer("le", 8),
+      registrationEndTime: new BN(config.registrationEndTime).toBuffer("le", 8),
+      feeBps: config.feeBps,
+      feePolicy: config.feePolicy,
+      matchingCapBps: config.matchingCapBps,
+      matchingAlgorithm: config.matchingAlgorithm,
+      pairwiseThreshold: new BN(config.pairwiseThreshold).toBuffer("le", 8),
+      identityIssuer: config.identityIssuer.toBuffer(),
+      curated: config.curated ? 1 : 0,
+      emptyRoundPolicy: config.emptyRoundPolicy,
+      vestingCliff: new BN(config.vestingCliff).toBuffer("le", 8),
+      vestingDuration: new BN(config.vestingDuration).toBuffer("le", 8),
     },
     data
   );
@@ -351,7 +397,7 @@ function createStartRoundInstruction(
     },
     {
       pubkey: roundOwnerPubkey,
-      isSigner: false,
+      isSigner: true,
       isWritable: false,
     },
     {
@@ -394,6 +440,26 @@ function createStartRoundInstruction(
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: feeRecipientPubkey,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: configPubkey,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: creatorPubkey,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -403,11 +469,25 @@ function createStartRoundInstruction(
   });
 }
 
+// project metadata, strings are packed with a u16 length prefix and followed by
+// the payout account, left unset here
+function encodeProjectMetadata(name: string, descriptionUri: string, imageUri: string): Buffer {
+  let fields = [name, descriptionUri, imageUri].map((field) => {
+    let bytes = Buffer.from(field, "utf8");
+    let len = Buffer.alloc(2);
+    len.writeUInt16LE(bytes.length, 0);
+    return Buffer.concat([len, bytes]);
+  });
+  return Buffer.concat([...fields, Buffer.alloc(32)]);
+}
+
 function registerProjectInstruction(
   programId: PublicKey,
   newProjectPubkey: PublicKey,
   roundPubkey: PublicKey,
-  projectOwnerPubkey: PublicKey
+  projectOwnerPubkey: PublicKey,
+  payerPubkey: PublicKey,
+  metadata: Buffer
 ): TransactionInstruction {
   const dataLayout = BufferLayout.struct([BufferLayout.u8("instruction")]);
 
@@ -428,7 +508,7 @@ function registerProjectInstruction(
     {
       pubkey: roundPubkey,
       isSigner: false,
-      isWritable: false,
+      isWritable: true,
     },
     {
       pubkey: projectOwnerPubkey,
@@ -440,21 +520,31 @@ function registerProjectInstruction(
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: payerPubkey,
+      isSigner: true,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
     keys,
     programId: programId,
-    data,
+    data: Buffer.concat([data, metadata]),
   });
 }
 
 function initVoterInstruction(
   programId: PublicKey,
   voterPubkey: PublicKey,
-  voterTokenHolderPubkey: PublicKey,
+  walletPubkey: PublicKey,
   projectPubkey: PublicKey,
-  fromPubkey: PublicKey
+  payerPubkey: PublicKey
 ): TransactionInstruction {
   const dataLayout = BufferLayout.struct([BufferLayout.u8("instruction")]);
 
@@ -473,8 +563,8 @@ function initVoterInstruction(
       isWritable: true,
     },
     {
-      pubkey: voterTokenHolderPubkey,
-      isSigner: false,
+      pubkey: walletPubkey,
+      isSigner: true,
       isWritable: false,
     },
     {
@@ -483,9 +573,9 @@ function initVoterInstruction(
       isWritable: false,
     },
     {
-      pubkey: fromPubkey,
+      pubkey: payerPubkey,
       isSigner: true,
-      isWritable: false,
+      isWritable: true,
     },
     {
       pubkey: SystemProgram.programId,
@@ -513,6 +603,7 @@ function donateInstruction(
   mintPubkey: PublicKey,
   toPubkey: PublicKey,
   fromAuthPubkey: PublicKey,
+  donorPubkey: PublicKey,
   amount: number,
   decimals: number
 ): TransactionInstruction {
@@ -556,13 +647,33 @@ function donateInstruction(
     {
       pubkey: fromAuthPubkey,
       isSigner: true,
-      isWritable: false,
+      isWritable: true,
     },
     {
       pubkey: SPLToken.TOKEN_PROGRAM_ID,
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: donorPubkey,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: SystemProgram.programId,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: SYSVAR_RENT_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -641,6 +752,11 @@ function voteInstruction(
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -740,6 +856,11 @@ function endRoundInstruction(
       isSigner: true,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -812,18 +933,43 @@ async function getVaultOwnerPubkey(round: PublicKey, programId: PublicKey): Prom
   return pda;
 }
 
-async function getVoterPubkey(
-  project: PublicKey,
-  voterTokenAccountPubkey: PublicKey,
+async function getVoterPubkey(project: PublicKey, wallet: PublicKey, programId: PublicKey): Promise<PublicKey> {
+  let [pda] = await PublicKey.findProgramAddress([Buffer.from("voter"), project.toBuffer(), wallet.toBuffer()], programId);
+  return pda;
+}
+
+async function getDonorPubkey(
+  round: PublicKey,
+  authority: PublicKey,
+  mint: PublicKey,
   programId: PublicKey
 ): Promise<PublicKey> {
-  let [pda] = await PublicKey.findProgramAddress([project.toBuffer(), voterTokenAccountPubkey.toBuffer()], programId);
+  let [pda] = await PublicKey.findProgramAddress(
+    [Buffer.from("donor"), round.toBuffer(), authority.toBuffer(), mint.toBuffer()],
+    programId
+  );
+  return pda;
+}
+
+async function getConfigPubkey(programId: PublicKey): Promise<PublicKey> {
+  let [pda] = await PublicKey.findProgramAddress([Buffer.from("config")], programId);
+  return pda;
+}
+
+async function getCreatorPubkey(wallet: PublicKey, programId: PublicKey): Promise<PublicKey> {
+  let [pda] = await PublicKey.findProgramAddress([Buffer.from("creator"), wallet.toBuffer()], programId);
   return pda;
 }
 
-async function CreateNewRound(connection: Connection, feePayer: Account, programId: PublicKey): Promise<any> {
+async function CreateNewRound(
+  connection: Connection,
+  feePayer: Account,
+  programId: PublicKey,
+  feeRecipientPubkey: PublicKey
+): Promise<any> {
   let owner = await newAccountWithLamports(connection, 10000000000);
   let round = new Account();
+  let now = Math.floor(Date.now() / 1000);
   let vaultOwnerPubkey = await getVaultOwnerPubkey(round.publicKey, programId);
   let vaultPubkey = await SPLToken.Token.getAssociatedTokenAddress(
     SPLToken.ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -850,7 +996,25 @@ async function CreateNewRound(connection: Connection, feePayer: Account, program
         owner.publicKey,
         vaultPubkey,
         vaultOwnerPubkey,
-        SPLToken.NATIVE_MINT
+        SPLToken.NATIVE_MINT,
+        feeRecipientPubkey,
+        await getConfigPubkey(programId),
+        await getCreatorPubkey(owner.publicKey, programId),
+        {
+          startTime: now,
+          endTime: now + 3600,
+          registrationEndTime: now + 3600,
+          feeBps: 500,
+          feePolicy: 0, // Total
+          matchingCapBps: 0,
+          matchingAlgorithm: 0, // Quadratic
+          pairwiseThreshold: 0,
+          identityIssuer: new PublicKey(0),
+          curated: false,
+          emptyRoundPolicy: 0, // RefundDonors
+          vestingCliff: 0,
+          vestingDuration: 0,
+        }
       )
     );
 
@@ -870,17 +1034,16 @@ async function CreateNewRound(connection: Connection, feePayer: Account, program
 async function InitVoter(
   connection: Connection,
   feePayer: Account,
-  from: Account,
+  wallet: Account,
   projectPubkey: PublicKey,
-  voterTokenAccountPubkey: PublicKey,
   programId: PublicKey
 ): Promise<any> {
-  let voterPubkey = await getVoterPubkey(projectPubkey, voterTokenAccountPubkey, programId);
+  let voterPubkey = await getVoterPubkey(projectPubkey, wallet.publicKey, programId);
   const tx = new Transaction().add(
-    initVoterInstruction(programId, voterPubkey, voterTokenAccountPubkey, projectPubkey, from.publicKey)
+    initVoterInstruction(programId, voterPubkey, wallet.publicKey, projectPubkey, feePayer.publicKey)
   );
 
-  let txHash = await sendAndConfirmTransaction(connection, tx, [feePayer, from], {
+  let txHash = await sendAndConfirmTransaction(connection, tx, [feePayer, wallet], {
     commitment: "singleGossip",
     preflightCommitment: "singleGossip",
   });
@@ -895,22 +1058,34 @@ async function RegisterProject(
   connection: Connection,
   feePayer: Account,
   roundPubkey: PublicKey,
-  programId: PublicKey
+  programId: PublicKey,
+  name: string
 ): Promise<any> {
   let owner = new Account();
   let project = new Account();
+  let metadata = encodeProjectMetadata(name, "", "");
+  let space = ProjectAccountDataLayout.span + metadata.length;
 
   const tx = new Transaction()
     .add(
       SystemProgram.createAccount({
         fromPubkey: feePayer.publicKey,
         newAccountPubkey: project.publicKey,
-        lamports: await connection.getMinimumBalanceForRentExemption(ProjectAccountDataLayout.span),
-        space: ProjectAccountDataLayout.span,
+        lamports: await connection.getMinimumBalanceForRentExemption(space),
+        space: space,
         programId: programId,
       })
     )
-    .add(registerProjectInstruction(programId, project.publicKey, roundPubkey, owner.publicKey));
+    .add(
+      registerProjectInstruction(
+        programId,
+        project.publicKey,
+        roundPubkey,
+        owner.publicKey,
+        feePayer.publicKey,
+        metadata
+      )
+    );
 
   let txHash = await sendAndConfirmTransaction(connection, tx, [feePayer, project], {
     commitment: "singleGossip",
@@ -936,8 +1111,19 @@ async function Donate(
   amount: number,
   decimals: number
 ): Promise<any> {
+  let donorPubkey = await getDonorPubkey(roundPubkey, fromAuth.publicKey, mintPubkey, programId);
   const tx = new Transaction().add(
-    donateInstruction(programId, roundPubkey, fromPubkey, mintPubkey, toPubkey, fromAuth.publicKey, amount, decimals)
+    donateInstruction(
+      programId,
+      roundPubkey,
+      fromPubkey,
+      mintPubkey,
+      toPubkey,
+      fromAuth.publicKey,
+      donorPubkey,
+      amount,
+      decimals
+    )
   );
 
   let txHash = await sendAndConfirmTransaction(connection, tx, [feePayer, fromAuth], {
@@ -1071,8 +1257,13 @@ type Round = {
   vault: PublicKey;
   owner: PublicKey;
   area: BN; // u256
+  endTime: BN; // i64
+  mint: PublicKey;
+  projectCount: BN; // u64
+  finishedTime: BN; // i64
 };
 
+// the full round account, only the fields of `Round` are decoded
 const RoundAccountDataLayout = BufferLayout.struct([
   BufferLayout.u8("roundStatus"),
   BufferLayout.blob(8, "fund"),
@@ -1080,6 +1271,43 @@ const RoundAccountDataLayout = BufferLayout.struct([
   BufferLayout.blob(32, "vault"),
   BufferLayout.blob(32, "owner"),
   BufferLayout.blob(32, "area"),
+  BufferLayout.blob(8, "startTime"),
+  BufferLayout.blob(8, "endTime"),
+  BufferLayout.blob(8, "registrationEndTime"),
+  BufferLayout.u16("feeBps"),
+  BufferLayout.blob(32, "feeRecipient"),
+  BufferLayout.u8("feePolicy"),
+  BufferLayout.u16("matchingCapBps"),
+  BufferLayout.blob(8, "projectCount"),
+  BufferLayout.u8("matchingSettled"),
+  BufferLayout.u8("matchingAlgorithm"),
+  BufferLayout.blob(8, "pairwiseThreshold"),
+  BufferLayout.blob(8, "pairCount"),
+  BufferLayout.blob(8, "pairsAccumulated"),
+  BufferLayout.blob(8, "pairsDistributed"),
+  BufferLayout.blob(32, "pairwiseArea"),
+  BufferLayout.blob(8, "paidOut"),
+  BufferLayout.blob(32, "mint"),
+  BufferLayout.blob(8, "votes"),
+  BufferLayout.blob(8, "deposited"),
+  BufferLayout.blob(8, "feeTotal"),
+  BufferLayout.blob(32, "identityIssuer"),
+  BufferLayout.u8("curated"),
+  BufferLayout.blob(32, "pendingOwner"),
+  BufferLayout.u8("emptyRoundPolicy"),
+  BufferLayout.blob(8, "approvedCount"),
+  BufferLayout.blob(8, "withdrawnCount"),
+  BufferLayout.blob(8, "mintCount"),
+  BufferLayout.blob(8, "vestingCliff"),
+  BufferLayout.blob(8, "vestingDuration"),
+  BufferLayout.blob(8, "escrowed"),
+  BufferLayout.blob(8, "settledCount"),
+  BufferLayout.blob(8, "cappedCount"),
+  BufferLayout.blob(32, "cappedArea"),
+  BufferLayout.blob(32, "lastSettledArea"),
+  BufferLayout.blob(8, "lastSettledIndex"),
+  BufferLayout.blob(8, "donated"),
+  BufferLayout.blob(8, "finishedTime"),
 ]);
 
 async function printRoundInfo(connection: Connection, round: PublicKey): Promise<void> {
@@ -1092,6 +1320,10 @@ async function printRoundInfo(connection: Connection, round: PublicKey): Promise
   console.log("fund", info.fund.toString());
   console.log("fee", info.fee.toString());
   console.log("area", info.area.toString());
+  console.log("mint", info.mint.toBase58());
+  console.log("project count", info.projectCount.toString());
+  console.log("end time", info.endTime.toString());
+  console.log("finished time", info.finishedTime.toString());
   console.log("");
 }
 
@@ -1108,6 +1340,10 @@ async function getRoundInfo(connection: Connection, round: PublicKey): Promise<R
   roundInfo.vault = new PublicKey(roundInfo.vault);
   roundInfo.owner = new PublicKey(roundInfo.owner);
   roundInfo.area = new BN(roundInfo.area, 10, "le");
+  roundInfo.endTime = new BN(roundInfo.endTime, 10, "le");
+  roundInfo.mint = new PublicKey(roundInfo.mint);
+  roundInfo.projectCount = new BN(roundInfo.projectCount, 10, "le");
+  roundInfo.finishedTime = new BN(roundInfo.finishedTime, 10, "le");
 
   return roundInfo;
 }
@@ -1119,8 +1355,11 @@ type Project = {
   votes: BN; // u64
   area: BN; // u256
   area_sqrt: BN; // u256
+  status: number; // u8
+  escrowed: BN; // u64
 };
 
+// the project, its metadata follows it in the account
 const ProjectAccountDataLayout = BufferLayout.struct([
   BufferLayout.blob(32, "round"),
   BufferLayout.blob(32, "owner"),
@@ -1128,6 +1367,15 @@ const ProjectAccountDataLayout = BufferLayout.struct([
   BufferLayout.blob(8, "votes"),
   BufferLayout.blob(32, "area"),
   BufferLayout.blob(32, "area_sqrt"),
+  BufferLayout.blob(8, "matched"),
+  BufferLayout.blob(8, "index"),
+  BufferLayout.blob(8, "voterCount"),
+  BufferLayout.blob(32, "pairwiseArea"),
+  BufferLayout.u8("status"),
+  BufferLayout.blob(32, "payer"),
+  BufferLayout.blob(8, "escrowed"),
+  BufferLayout.blob(8, "claimed"),
+  BufferLayout.u8("flagged"),
 ]);
 
 async function getProjectInfo(connection: Connection, project: PublicKey): Promise<Project> {
@@ -1144,6 +1392,7 @@ async function getProjectInfo(connection: Connection, project: PublicKey): Promi
   projectInfo.votes = new BN(projectInfo.votes, 10, "le");
   projectInfo.area = new BN(projectInfo.area, 10, "le");
   projectInfo.area_sqrt = new BN(projectInfo.area_sqrt, 10, "le");
+  projectInfo.escrowed = new BN(projectInfo.escrowed, 10, "le");
 
   return projectInfo;
 }
@@ -1157,19 +1406,27 @@ async function printProjectInfo(connection: Connection, project: PublicKey, titl
   console.log("withdraw:", info.withdraw);
   console.log("votes", info.votes.toString());
   console.log("area", info.area.toString());
+  console.log("status", info.status);
+  console.log("escrowed", info.escrowed.toString());
   console.log("");
 }
 
 type Voter = {
   isInit: boolean;
   votes: BN; // u64
-  votes_sqrt: BN; // u64
+  votes_sqrt: BN; // u256
+  mint: PublicKey;
+  payer: PublicKey;
 };
 
 const VoterAccountDataLayout = BufferLayout.struct([
   BufferLayout.u8("isInit"),
   BufferLayout.blob(8, "votes"),
-  BufferLayout.blob(8, "votes_sqrt"),
+  BufferLayout.blob(32, "votes_sqrt"),
+  BufferLayout.blob(8, "amount"),
+  BufferLayout.blob(32, "mint"),
+  BufferLayout.u16("weightBps"),
+  BufferLayout.blob(32, "payer"),
 ]);
 
 async function getVoterInfo(connection: Connection, voter: PublicKey): Promise<Voter> {
@@ -1183,6 +1440,8 @@ async function getVoterInfo(connection: Connection, voter: PublicKey): Promise<V
   voterInfo.isInit = voterInfo.isInit == 1;
   voterInfo.votes = new BN(voterInfo.votes, 10, "le");
   voterInfo.votes_sqrt = new BN(voterInfo.votes_sqrt, 10, "le");
+  voterInfo.mint = new PublicKey(voterInfo.mint);
+  voterInfo.payer = new PublicKey(voterInfo.payer);
 
   return voterInfo;
 }
@@ -1194,6 +1453,8 @@ async function printVoterInfo(connection: Connection, voter: PublicKey, title =
   console.log("isInit:", info.isInit);
   console.log("votes:", info.votes.toString());
   console.log("votes sqrt:", info.votes_sqrt.toString());
+  console.log("mint:", info.mint.toBase58());
+  console.log("payer:", info.payer.toBase58());
   console.log("");
 }
 
@@ -6,6 +6,7 @@ import {
   PublicKey,
   TransactionInstruction,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
   Account,
 } from "@solana/web3.js";
 import React, { useState } from "react";
@@ -28,6 +29,7 @@ export default () => {
   const [pubkey, setPubkey] = useState(PublicKey.default);
 
   const [registerProjectRoundPubkey, setRegisterProjectRoundPubkey] = useState("");
+  const [registerProjectName, setRegisterProjectName] = useState("");
   const [voteProjectPubkey, setVoteProjectPubkey] = useState("");
   const [voteAmount, setVoteAmount] = useState("");
   const [initVoterProjectPubkey, setInitVoterProjectPubkey] = useState("");
@@ -47,9 +49,47 @@ export default () => {
     BufferLayout.blob(32, "vault"),
     BufferLayout.blob(32, "owner"),
     BufferLayout.blob(32, "area"),
+    BufferLayout.blob(8, "startTime"),
+    BufferLayout.blob(8, "endTime"),
+    BufferLayout.blob(8, "registrationEndTime"),
+    BufferLayout.u16("feeBps"),
+    BufferLayout.blob(32, "feeRecipient"),
+    BufferLayout.u8("feePolicy"),
+    BufferLayout.u16("matchingCapBps"),
+    BufferLayout.blob(8, "projectCount"),
+    BufferLayout.u8("matchingSettled"),
+    BufferLayout.u8("matchingAlgorithm"),
+    BufferLayout.blob(8, "pairwiseThreshold"),
+    BufferLayout.blob(8, "pairCount"),
+    BufferLayout.blob(8, "pairsAccumulated"),
+    BufferLayout.blob(8, "pairsDistributed"),
+    BufferLayout.blob(32, "pairwiseArea"),
+    BufferLayout.blob(8, "paidOut"),
+    BufferLayout.blob(32, "mint"),
+    BufferLayout.blob(8, "votes"),
+    BufferLayout.blob(8, "deposited"),
+    BufferLayout.blob(8, "feeTotal"),
+    BufferLayout.blob(32, "identityIssuer"),
+    BufferLayout.u8("curated"),
+    BufferLayout.blob(32, "pendingOwner"),
+    BufferLayout.u8("emptyRoundPolicy"),
+    BufferLayout.blob(8, "approvedCount"),
+    BufferLayout.blob(8, "withdrawnCount"),
+    BufferLayout.blob(8, "mintCount"),
+    BufferLayout.blob(8, "vestingCliff"),
+    BufferLayout.blob(8, "vestingDuration"),
+    BufferLayout.blob(8, "escrowed"),
+    BufferLayout.blob(8, "settledCount"),
+    BufferLayout.blob(8, "cappedCount"),
+    BufferLayout.blob(32, "cappedArea"),
+    BufferLayout.blob(32, "lastSettledArea"),
+    BufferLayout.blob(8, "lastSettledIndex"),
+    BufferLayout.blob(8, "donated"),
+    BufferLayout.blob(8, "finishedTime"),
   ]);
   const [getRoundInfoPubkey, setGetRoundInfoPubkey] = useState("");
 
+  // the project, its metadata follows it in the account
   const ProjectAccountDataLayout = BufferLayout.struct([
     BufferLayout.blob(32, "round"),
     BufferLayout.blob(32, "owner"),
@@ -57,13 +97,26 @@ export default () => {
     BufferLayout.blob(8, "votes"),
     BufferLayout.blob(32, "area"),
     BufferLayout.blob(32, "area_sqrt"),
+    BufferLayout.blob(8, "matched"),
+    BufferLayout.blob(8, "index"),
+    BufferLayout.blob(8, "voterCount"),
+    BufferLayout.blob(32, "pairwiseArea"),
+    BufferLayout.u8("status"),
+    BufferLayout.blob(32, "payer"),
+    BufferLayout.blob(8, "escrowed"),
+    BufferLayout.blob(8, "claimed"),
+    BufferLayout.u8("flagged"),
   ]);
   const [getProjectInfoPubkey, setGetProjectInfoPubkey] = useState("");
 
   const VoterAccountDataLayout = BufferLayout.struct([
     BufferLayout.u8("isInit"),
     BufferLayout.blob(8, "votes"),
-    BufferLayout.blob(8, "votes_sqrt"),
+    BufferLayout.blob(32, "votes_sqrt"),
+    BufferLayout.blob(8, "amount"),
+    BufferLayout.blob(32, "mint"),
+    BufferLayout.u16("weightBps"),
+    BufferLayout.blob(32, "payer"),
   ]);
   const [getVoterInfoPubkey, setGetVoterInfoPubkey] = useState("");
 
@@ -96,8 +149,24 @@ export default () => {
       let round = new Account();
       let vaultOwnerPubkey = await getVaultOwnerPubkey(round.publicKey, programId);
       let vaultPubkey = await getVaultPubkey(vaultOwnerPubkey, SPLToken.NATIVE_MINT)
+      let now = Math.floor(Date.now() / 1000);
 
-      const tx = new Transaction()
+      // the fee of the round goes to the associated token account of the wallet
+      const tx = new Transaction();
+      let feeRecipientPubkey = await getVaultPubkey(wallet.publicKey, SPLToken.NATIVE_MINT);
+      if ((await connection.getAccountInfo(feeRecipientPubkey)) === null) {
+        tx.add(
+          SPLToken.Token.createAssociatedTokenAccountInstruction(
+            SPLToken.ASSOCIATED_TOKEN_PROGRAM_ID,
+            SPLToken.TOKEN_PROGRAM_ID,
+            SPLToken.NATIVE_MINT,
+            feeRecipientPubkey,
+            wallet.publicKey,
+            wallet.publicKey
+          )
+        );
+      }
+      tx
         .add(
           SystemProgram.createAccount({
             fromPubkey: wallet.publicKey,
@@ -115,7 +184,25 @@ export default () => {
             wallet.publicKey,
             vaultPubkey,
             vaultOwnerPubkey,
-            SPLToken.NATIVE_MINT
+            SPLToken.NATIVE_MINT,
+            feeRecipientPubkey,
+            await getConfigPubkey(programId),
+            await getCreatorPubkey(wallet.publicKey, programId),
+            {
+              startTime: now,
+              endTime: now + 7 * 24 * 3600,
+              registrationEndTime: now + 7 * 24 * 3600,
+              feeBps: 0,
+              feePolicy: 0, // Total
+              matchingCapBps: 0,
+              matchingAlgorithm: 0, // Quadratic
+              pairwiseThreshold: 0,
+              identityIssuer: new PublicKey(0),
+              curated: false,
+              emptyRoundPolicy: 0, // RefundDonors
+              vestingCliff: 0,
+              vestingDuration: 0,
+            }
           )
         );
 
@@ -136,22 +223,33 @@ export default () => {
     }
   }
 
-  async function registerProject(roundPubkey: string) {
+  async function registerProject(roundPubkey: string, name: string) {
     try {
       let round = new PublicKey(roundPubkey);
       let project = new Account();
+      let metadata = encodeProjectMetadata(name, "", "");
+      let space = ProjectAccountDataLayout.span + metadata.length;
 
       const tx = new Transaction()
         .add(
           SystemProgram.createAccount({
             fromPubkey: wallet.publicKey,
             newAccountPubkey: project.publicKey,
-            lamports: await connection.getMinimumBalanceForRentExemption(ProjectAccountDataLayout.span),
-            space: ProjectAccountDataLayout.span,
+            lamports: await connection.getMinimumBalanceForRentExemption(space),
+            space: space,
             programId: programId,
           })
         )
-        .add(registerProjectInstruction(programId, project.publicKey, round, wallet.publicKey));
+        .add(
+          registerProjectInstruction(
+            programId,
+            project.publicKey,
+            round,
+            wallet.publicKey,
+            wallet.publicKey,
+            metadata
+          )
+        );
 
       let { blockhash } = await connection.getRecentBlockhash();
       tx.recentBlockhash = blockhash;
@@ -200,6 +298,7 @@ export default () => {
             SPLToken.NATIVE_MINT,
             roundInfo.vault,
             wallet.publicKey,
+            await getDonorPubkey(round, wallet.publicKey, SPLToken.NATIVE_MINT, programId),
             amount,
             9
           )
@@ -232,13 +331,7 @@ export default () => {
 
   async function initVoter(projectPubkey: string) {
     try {
-      let assoAccount = await SPLToken.Token.getAssociatedTokenAddress(
-        SPLToken.ASSOCIATED_TOKEN_PROGRAM_ID,
-        SPLToken.TOKEN_PROGRAM_ID,
-        SPLToken.NATIVE_MINT,
-        wallet.publicKey
-      );
-      let voterPubkey = await getVoterPubkey(new PublicKey(projectPubkey), assoAccount, programId);
+      let voterPubkey = await getVoterPubkey(new PublicKey(projectPubkey), wallet.publicKey, programId);
       let voterInfo = await getVoterInfo(voterPubkey.toBase58());
       if (voterInfo !== undefined && voterInfo.isInit) {
         appendOutput("voter: " + voterPubkey.toBase58() + " already init");
@@ -246,7 +339,7 @@ export default () => {
       }
 
       const tx = new Transaction().add(
-        initVoterInstruction(programId, voterPubkey, assoAccount, new PublicKey(projectPubkey), wallet.publicKey)
+        initVoterInstruction(programId, voterPubkey, wallet.publicKey, new PublicKey(projectPubkey), wallet.publicKey)
       );
 
       let { blockhash } = await connection.getRecentBlockhash();
@@ -272,7 +365,7 @@ export default () => {
         SPLToken.NATIVE_MINT,
         wallet.publicKey
       );
-      let voterPubkey = await getVoterPubkey(new PublicKey(projectPubkey), assoAccount, programId);
+      let voterPubkey = await getVoterPubkey(new PublicKey(projectPubkey), wallet.publicKey, programId);
       let voterInfo = await getVoterInfo(voterPubkey.toBase58());
       if (voterInfo === undefined || !voterInfo.isInit) {
         appendOutput("please init voter for project " + projectPubkey + " first" + "\n" + output);
@@ -508,6 +601,10 @@ export default () => {
       encodeInfo.vault = new PublicKey(encodeInfo.vault);
       encodeInfo.owner = new PublicKey(encodeInfo.owner);
       encodeInfo.area = new BN(encodeInfo.area, 10, "le");
+      encodeInfo.endTime = new BN(encodeInfo.endTime, 10, "le");
+      encodeInfo.mint = new PublicKey(encodeInfo.mint);
+      encodeInfo.projectCount = new BN(encodeInfo.projectCount, 10, "le");
+      encodeInfo.finishedTime = new BN(encodeInfo.finishedTime, 10, "le");
 
       appendOutput(`
       ================ Round ================\n
@@ -516,7 +613,11 @@ export default () => {
       vault: ${encodeInfo.vault.toBase58()}\n
       fund: ${encodeInfo.fund.toString()}\n
       fee: ${encodeInfo.fee.toString()}\n
-      area: ${encodeInfo.area.toString()}`);
+      area: ${encodeInfo.area.toString()}\n
+      mint: ${encodeInfo.mint.toBase58()}\n
+      project count: ${encodeInfo.projectCount.toString()}\n
+      end time: ${encodeInfo.endTime.toString()}\n
+      finished time: ${encodeInfo.finishedTime.toString()}`);
 
       return encodeInfo;
     } catch (e) {
@@ -539,6 +640,7 @@ export default () => {
       encodeInfo.votes = new BN(encodeInfo.votes, 10, "le");
       encodeInfo.area = new BN(encodeInfo.area, 10, "le");
       encodeInfo.area_sqrt = new BN(encodeInfo.area_sqrt, 10, "le");
+      encodeInfo.escrowed = new BN(encodeInfo.escrowed, 10, "le");
 
       appendOutput(`
       ================ Project ================\n
@@ -547,7 +649,9 @@ export default () => {
       withdraw: ${encodeInfo.withdraw}\n
       votes: ${encodeInfo.votes.toString()}\n
       area: ${encodeInfo.area.toString()}\n
-      area_sqrt: ${encodeInfo.area_sqrt.toString()}`);
+      area_sqrt: ${encodeInfo.area_sqrt.toString()}\n
+      status: ${encodeInfo.status}\n
+      escrowed: ${encodeInfo.escrowed.toString()}`);
 
       return encodeInfo;
     } catch (e) {
@@ -566,12 +670,16 @@ export default () => {
       encodeInfo.isInit = encodeInfo.isInit == 1;
       encodeInfo.votes = new BN(encodeInfo.votes, 10, "le");
       encodeInfo.votes_sqrt = new BN(encodeInfo.votes_sqrt, 10, "le");
+      encodeInfo.mint = new PublicKey(encodeInfo.mint);
+      encodeInfo.payer = new PublicKey(encodeInfo.payer);
 
       appendOutput(`
       ================ Voter ================\n
       isInit: ${encodeInfo.isInit}\n
       votes: ${encodeInfo.votes.toString()}\n
-      votes sqrt: ${encodeInfo.votes_sqrt.toString()}`);
+      votes sqrt: ${encodeInfo.votes_sqrt.toString()}\n
+      mint: ${encodeInfo.mint.toBase58()}\n
+      payer: ${encodeInfo.payer.toBase58()}`);
       return encodeInfo;
     } catch (e) {
       appendOutput(e.message);
@@ -617,7 +725,15 @@ export default () => {
               onChange={(v) => setRegisterProjectRoundPubkey(v.target.value)}
               placeholder="round pubkey (base58)"
             ></input>
-            <button onClick={() => registerProject(registerProjectRoundPubkey)}>Register New Project</button>
+            <input
+              type="text"
+              value={registerProjectName}
+              onChange={(v) => setRegisterProjectName(v.target.value)}
+              placeholder="project name"
+            ></input>
+            <button onClick={() => registerProject(registerProjectRoundPubkey, registerProjectName)}>
+              Register New Project
+            </button>
           </div>
           <div>
             <input
@@ -731,6 +847,22 @@ enum Instruction {
   WithdrawFee,
 }
 
+type RoundConfig = {
+  startTime: number; // i64
+  endTime: number; // i64
+  registrationEndTime: number; // i64
+  feeBps: number; // u16
+  feePolicy: number; // u8
+  matchingCapBps: number; // u16
+  matchingAlgorithm: number; // u8
+  pairwiseThreshold: number; // u64
+  identityIssuer: PublicKey;
+  curated: boolean;
+  emptyRoundPolicy: number; // u8
+  vestingCliff: number; // i64
+  vestingDuration: number; // i64
+};
+
 function createStartRoundInstruction(
   programId: PublicKey,
   newRoundPubkey: PublicKey,
@@ -738,14 +870,46 @@ function createStartRoundInstruction(
   funderPubkey: PublicKey,
   associatedTokenAccountPubkey: PublicKey,
   walletAccountPubkey: PublicKey,
-  mintPubkey: PublicKey
+  mintPubkey: PublicKey,
+  feeRecipientPubkey: PublicKey,
+  configPubkey: PublicKey,
+  creatorPubkey: PublicKey,
+  config: RoundConfig
 ): TransactionInstruction {
-  const dataLayout = BufferLayout.struct([BufferLayout.u8("instruction")]);
+  const dataLayout = BufferLayout.struct([
+    BufferLayout.u8("instruction"),
+    BufferLayout.blob(8, "startTime"),
+    BufferLayout.blob(8, "endTime"),
+    BufferLayout.blob(8, "registrationEndTime"),
+    BufferLayout.u16("feeBps"),
+    BufferLayout.u8("feePolicy"),
+    BufferLayout.u16("matchingCapBps"),
+    BufferLayout.u8("matchingAlgorithm"),
+    BufferLayout.blob(8, "pairwiseThreshold"),
+    BufferLayout.blob(32, "identityIssuer"),
+    BufferLayout.u8("curated"),
+    BufferLayout.u8("emptyRoundPolicy"),
+    BufferLayout.blob(8, "vestingCliff"),
+    BufferLayout.blob(8, "vestingDuration"),
+  ]);
 
   const data = Buffer.alloc(dataLayout.span);
   dataLayout.encode(
     {
       instruction: Instruction.StartRound,
+      startTime: Buffer.from(new BN(config.startTime).toArray("le", 8)),
+      endTime: Buffer.from(new BN(config.endTime).toArray("le", 8)),
+      registrationEndTime: Buffer.from(new BN(config.registrationEndTime).toArray("le", 8)),
+      feeBps: config.feeBps,
+      feePolicy: config.feePolicy,
+      matchingCapBps: config.matchingCapBps,
+      matchingAlgorithm: config.matchingAlgorithm,
+      pairwiseThreshold: Buffer.from(new BN(config.pairwiseThreshold).toArray("le", 8)),
+      identityIssuer: config.identityIssuer.toBuffer(),
+      curated: config.curated ? 1 : 0,
+      emptyRoundPolicy: config.emptyRoundPolicy,
+      vestingCliff: Buffer.from(new BN(config.vestingCliff).toArray("le", 8)),
+      vestingDuration: Buffer.from(new BN(config.vestingDuration).toArray("le", 8)),
     },
     data
   );
@@ -758,7 +922,7 @@ function createStartRoundInstruction(
     },
     {
       pubkey: roundOwnerPubkey,
-      isSigner: false,
+      isSigner: true,
       isWritable: false,
     },
     {
@@ -801,6 +965,26 @@ function createStartRoundInstruction(
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: feeRecipientPubkey,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: configPubkey,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: creatorPubkey,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -810,11 +994,25 @@ function createStartRoundInstruction(
   });
 }
 
+// project metadata, strings are packed with a u16 length prefix and followed by
+// the payout account, left unset here
+function encodeProjectMetadata(name: string, descriptionUri: string, imageUri: string): Buffer {
+  let fields = [name, descriptionUri, imageUri].map((field) => {
+    let bytes = Buffer.from(field, "utf8");
+    let len = Buffer.alloc(2);
+    len.writeUInt16LE(bytes.length, 0);
+    return Buffer.concat([len, bytes]);
+  });
+  return Buffer.concat([...fields, Buffer.alloc(32)]);
+}
+
 function registerProjectInstruction(
   programId: PublicKey,
   newProjectPubkey: PublicKey,
   roundPubkey: PublicKey,
-  projectOwnerPubkey: PublicKey
+  projectOwnerPubkey: PublicKey,
+  payerPubkey: PublicKey,
+  metadata: Buffer
 ): TransactionInstruction {
   const dataLayout = BufferLayout.struct([BufferLayout.u8("instruction")]);
 
@@ -835,7 +1033,7 @@ function registerProjectInstruction(
     {
       pubkey: roundPubkey,
       isSigner: false,
-      isWritable: false,
+      isWritable: true,
     },
     {
       pubkey: projectOwnerPubkey,
@@ -847,21 +1045,31 @@ function registerProjectInstruction(
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: payerPubkey,
+      isSigner: true,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
     keys,
     programId: programId,
-    data,
+    data: Buffer.concat([data, metadata]),
   });
 }
 
 function initVoterInstruction(
   programId: PublicKey,
   voterPubkey: PublicKey,
-  voterTokenHolderPubkey: PublicKey,
+  walletPubkey: PublicKey,
   projectPubkey: PublicKey,
-  fromPubkey: PublicKey
+  payerPubkey: PublicKey
 ): TransactionInstruction {
   const dataLayout = BufferLayout.struct([BufferLayout.u8("instruction")]);
 
@@ -880,8 +1088,8 @@ function initVoterInstruction(
       isWritable: true,
     },
     {
-      pubkey: voterTokenHolderPubkey,
-      isSigner: false,
+      pubkey: walletPubkey,
+      isSigner: true,
       isWritable: false,
     },
     {
@@ -890,9 +1098,9 @@ function initVoterInstruction(
       isWritable: false,
     },
     {
-      pubkey: fromPubkey,
+      pubkey: payerPubkey,
       isSigner: true,
-      isWritable: false,
+      isWritable: true,
     },
     {
       pubkey: SystemProgram.programId,
@@ -920,6 +1128,7 @@ function donateInstruction(
   mintPubkey: PublicKey,
   toPubkey: PublicKey,
   fromAuthPubkey: PublicKey,
+  donorPubkey: PublicKey,
   amount: number,
   decimals: number
 ): TransactionInstruction {
@@ -963,13 +1172,33 @@ function donateInstruction(
     {
       pubkey: fromAuthPubkey,
       isSigner: true,
-      isWritable: false,
+      isWritable: true,
     },
     {
       pubkey: SPLToken.TOKEN_PROGRAM_ID,
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: donorPubkey,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: SystemProgram.programId,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: SYSVAR_RENT_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -1048,6 +1277,11 @@ function voteInstruction(
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -1147,6 +1381,11 @@ function endRoundInstruction(
       isSigner: true,
       isWritable: false,
     },
+    {
+      pubkey: SYSVAR_CLOCK_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new TransactionInstruction({
@@ -1228,11 +1467,30 @@ async function getVaultPubkey(owner: PublicKey, mint: PublicKey): Promise<Public
   )[0]
 }
 
-async function getVoterPubkey(
-  project: PublicKey,
-  voterTokenAccountPubkey: PublicKey,
+async function getVoterPubkey(project: PublicKey, wallet: PublicKey, programId: PublicKey): Promise<PublicKey> {
+  let [pda] = await PublicKey.findProgramAddress([Buffer.from("voter"), project.toBuffer(), wallet.toBuffer()], programId);
+  return pda;
+}
+
+async function getDonorPubkey(
+  round: PublicKey,
+  authority: PublicKey,
+  mint: PublicKey,
   programId: PublicKey
 ): Promise<PublicKey> {
-  let [pda] = await PublicKey.findProgramAddress([project.toBuffer(), voterTokenAccountPubkey.toBuffer()], programId);
+  let [pda] = await PublicKey.findProgramAddress(
+    [Buffer.from("donor"), round.toBuffer(), authority.toBuffer(), mint.toBuffer()],
+    programId
+  );
+  return pda;
+}
+
+async function getConfigPubkey(programId: PublicKey): Promise<PublicKey> {
+  let [pda] = await PublicKey.findProgramAddress([Buffer.from("config")], programId);
+  return pda;
+}
+
+async function getCreatorPubkey(wallet: PublicKey, programId: PublicKey): Promise<PublicKey> {
+  let [pda] = await PublicKey.findProgramAddress([Buffer.from("creator"), wallet.toBuffer()], programId);
   return pda;
 }
@@ -30,6 +30,150 @@ pub enum QFError {
 
     #[error("voter mismatch")]
     VoterMismatch,
+
+    #[error("invalid round time")]
+    InvalidRoundTime,
+
+    #[error("round has not started")]
+    RoundNotStarted,
+
+    #[error("round has ended")]
+    RoundEnded,
+
+    #[error("project registration has closed")]
+    RegistrationClosed,
+
+    #[error("round has not reached its end time")]
+    RoundNotEnded,
+
+    #[error("fee rate exceeds the maximum")]
+    InvalidFeeRate,
+
+    #[error("fee recipient does not match")]
+    FeeRecipientMismatch,
+
+    #[error("invalid matching cap")]
+    InvalidMatchingCap,
+
+    #[error("matching of the round is not settled")]
+    MatchingNotSettled,
+
+    #[error("matching of the round has already settled")]
+    MatchingAlreadySettled,
+
+    #[error("projects of the round are incomplete")]
+    IncompleteProjects,
+
+    #[error("pairwise threshold must be positive")]
+    InvalidPairwiseThreshold,
+
+    #[error("matching algorithm of the round does not match")]
+    MatchingAlgorithmMismatch,
+
+    #[error("voter pair does not match")]
+    VoterPairMismatch,
+
+    #[error("voters of the pair do not both vote the project")]
+    PairNotOverlapped,
+
+    #[error("voter pair has already processed the project")]
+    PairAlreadyProcessed,
+
+    #[error("voter pairs of the round are not all accumulated")]
+    PairwiseNotAccumulated,
+
+    #[error("donor mismatch")]
+    DonorMismatch,
+
+    #[error("round has already paid out")]
+    RoundAlreadyPaidOut,
+
+    #[error("mint mismatch")]
+    MintMismatch,
+
+    #[error("invalid price")]
+    InvalidPrice,
+
+    #[error("price feed mismatch")]
+    PriceFeedMismatch,
+
+    #[error("mint is already accepted by the round")]
+    MintAlreadyAccepted,
+
+    #[error("project has not withdrawn from the round vault")]
+    ProjectNotWithdrawn,
+
+    #[error("attestation mismatch")]
+    AttestationMismatch,
+
+    #[error("invalid trust score")]
+    InvalidTrustScore,
+
+    #[error("project is not approved")]
+    ProjectNotApproved,
+
+    #[error("project is not pending")]
+    ProjectNotPending,
+
+    #[error("not a curator of the round")]
+    NotCurator,
+
+    #[error("invalid project metadata")]
+    InvalidMetadata,
+
+    #[error("payout mismatch")]
+    PayoutMismatch,
+
+    #[error("no pending authority")]
+    NoPendingAuthority,
+
+    #[error("config mismatch")]
+    ConfigMismatch,
+
+    #[error("round creator is not allowed")]
+    CreatorNotAllowed,
+
+    #[error("math operation overflowed")]
+    MathOverflow,
+
+    #[error("round is not empty")]
+    RoundNotEmpty,
+
+    #[error("fee is not withdrawn")]
+    FeeNotWithdrawn,
+
+    #[error("donations are not refunded")]
+    DonationsNotRefunded,
+
+    #[error("round has accepted mints")]
+    RoundHasMints,
+
+    #[error("payer mismatch")]
+    PayerMismatch,
+
+    #[error("payout account is not set")]
+    PayoutNotSet,
+
+    #[error("invalid vesting schedule")]
+    InvalidVestingSchedule,
+
+    #[error("round is not vesting")]
+    NotVestingRound,
+
+    #[error("project is flagged")]
+    ProjectFlagged,
+
+    #[error("escrow is outstanding")]
+    EscrowOutstanding,
+
+    #[error("mint is not allowed")]
+    MintNotAllowed,
+
+    #[error("upgrade authority mismatch")]
+    UpgradeAuthorityMismatch,
+
+    #[error("votes not refunded")]
+    VotesNotRefunded,
 }
 impl From<QFError> for ProgramError {
     fn from(e: QFError) -> Self {
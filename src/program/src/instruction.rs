@@ -1,18 +1,89 @@
-use solana_program::program_error::ProgramError;
+use crate::state::{EmptyRoundPolicy, FeePolicy, MatchingAlgorithm, ProjectMetadata};
+use num_enum::TryFromPrimitive;
+use solana_program::{
+    bpf_loader_upgradeable,
+    clock::UnixTimestamp,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use spl_token::native_mint;
 use std::convert::TryInto;
 use std::mem::size_of;
 
+/// Parameters of a new round
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoundConfig {
+    /// Donations and votes are accepted from the start time until the end time
+    pub start_time: UnixTimestamp,
+    pub end_time: UnixTimestamp,
+    pub registration_end_time: UnixTimestamp,
+    pub fee_bps: u16,
+    pub fee_policy: FeePolicy,
+    pub matching_cap_bps: u16,
+    pub matching_algorithm: MatchingAlgorithm,
+    pub pairwise_threshold: u64,
+    /// Issuer whose attestations gate the matching of votes, the default
+    /// pubkey leaves the round ungated
+    pub identity_issuer: Pubkey,
+    /// Projects of a curated round wait for the round owner or a curator to
+    /// approve them before they receive votes
+    pub curated: bool,
+    pub empty_round_policy: EmptyRoundPolicy,
+    /// Vesting schedule of matching funds in seconds after the round finished,
+    /// a zero duration pays them out right away
+    pub vesting_cliff: UnixTimestamp,
+    pub vesting_duration: UnixTimestamp,
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub enum QFInstruction {
-    StartRound,
+    StartRound(RoundConfig),
     Donate { amount: u64, decimals: u8 },
-    RegisterProject,
+    RegisterProject(ProjectMetadata),
     InitVoter,
     Vote { amount: u64, decimals: u8 },
     Withdraw,
     EndRound,
     WithdrawFee,
+    FinalizeRound,
+    SettleMatching,
+    InitVoterPair,
+    AccumulateVoterPair,
+    DistributeVoterPair,
+    CancelRound,
+    RefundDonation,
+    RefundVote,
+    InitPriceFeed { price: u64 },
+    SetPrice { price: u64 },
+    AddMint,
+    WithdrawMint,
+    WithdrawMintFee,
+    DonateNative { amount: u64 },
+    VoteNative { amount: u64 },
+    Attest { trust_bps: u16 },
+    RevokeAttestation,
+    AddCurator,
+    RemoveCurator,
+    ApproveProject,
+    RejectProject,
+    UpdateProjectMetadata(ProjectMetadata),
+    SetRoundAuthority,
+    AcceptRoundAuthority,
+    InitConfig,
+    AddCreator,
+    RemoveCreator,
+    CloseRound,
+    CloseVoter,
+    CloseProject,
+    DistributeToProject,
+    ClaimVested,
+    ClawBack,
+    RollOver,
+    CloseDonor,
 }
 
 impl QFInstruction {
@@ -21,14 +92,57 @@ impl QFInstruction {
             .split_first()
             .ok_or(ProgramError::InvalidInstructionData)?;
         Ok(match tag {
-            0 => Self::StartRound,
-            1 | 4 => {
-                let (amount, rest) = rest.split_at(8);
-                let amount = amount
-                    .try_into()
-                    .ok()
-                    .map(u64::from_le_bytes)
+            0 => {
+                let (start_time, rest) = Self::unpack_i64(rest)?;
+                let (end_time, rest) = Self::unpack_i64(rest)?;
+                let (registration_end_time, rest) = Self::unpack_i64(rest)?;
+                let (fee_bps, rest) = Self::unpack_u16(rest)?;
+                let (&fee_policy, rest) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let fee_policy = FeePolicy::try_from_primitive(fee_policy)
+                    .or(Err(ProgramError::InvalidInstructionData))?;
+                let (matching_cap_bps, rest) = Self::unpack_u16(rest)?;
+                let (&matching_algorithm, rest) = rest
+                    .split_first()
                     .ok_or(ProgramError::InvalidInstructionData)?;
+                let matching_algorithm = MatchingAlgorithm::try_from_primitive(matching_algorithm)
+                    .or(Err(ProgramError::InvalidInstructionData))?;
+                let (pairwise_threshold, rest) = Self::unpack_u64(rest)?;
+                let (identity_issuer, rest) = Self::unpack_pubkey(rest)?;
+                let (&curated, rest) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let curated = match curated {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(ProgramError::InvalidInstructionData),
+                };
+                let (&empty_round_policy, rest) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let empty_round_policy = EmptyRoundPolicy::try_from_primitive(empty_round_policy)
+                    .or(Err(ProgramError::InvalidInstructionData))?;
+                let (vesting_cliff, rest) = Self::unpack_i64(rest)?;
+                let (vesting_duration, _rest) = Self::unpack_i64(rest)?;
+                Self::StartRound(RoundConfig {
+                    start_time,
+                    end_time,
+                    registration_end_time,
+                    fee_bps,
+                    fee_policy,
+                    matching_cap_bps,
+                    matching_algorithm,
+                    pairwise_threshold,
+                    identity_issuer,
+                    curated,
+                    empty_round_policy,
+                    vesting_cliff,
+                    vesting_duration,
+                })
+            }
+            1 | 4 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
                 let (&decimals, _rest) = rest
                     .split_first()
                     .ok_or(ProgramError::InvalidInstructionData)?;
@@ -38,11 +152,70 @@ impl QFInstruction {
                     _ => unreachable!(),
                 }
             }
-            2 => Self::RegisterProject,
+            2 | 30 => {
+                let metadata =
+                    ProjectMetadata::unpack(rest).or(Err(ProgramError::InvalidInstructionData))?;
+                match tag {
+                    2 => Self::RegisterProject(metadata),
+                    30 => Self::UpdateProjectMetadata(metadata),
+                    _ => unreachable!(),
+                }
+            }
             3 => Self::InitVoter,
             5 => Self::Withdraw,
             6 => Self::EndRound,
             7 => Self::WithdrawFee,
+            8 => Self::FinalizeRound,
+            9 => Self::SettleMatching,
+            10 => Self::InitVoterPair,
+            11 => Self::AccumulateVoterPair,
+            12 => Self::DistributeVoterPair,
+            13 => Self::CancelRound,
+            14 => Self::RefundDonation,
+            15 => Self::RefundVote,
+            16 | 17 => {
+                let (price, _rest) = Self::unpack_u64(rest)?;
+                match tag {
+                    16 => Self::InitPriceFeed { price },
+                    17 => Self::SetPrice { price },
+                    _ => unreachable!(),
+                }
+            }
+            18 => Self::AddMint,
+            19 => Self::WithdrawMint,
+            20 => Self::WithdrawMintFee,
+            21 | 22 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                match tag {
+                    21 => Self::DonateNative { amount },
+                    22 => Self::VoteNative { amount },
+                    _ => unreachable!(),
+                }
+            }
+            // 23 is reserved, it was MigrateVoter and is not reused so an old
+            // client fails instead of sending another instruction
+            24 => {
+                let (trust_bps, _rest) = Self::unpack_u16(rest)?;
+                Self::Attest { trust_bps }
+            }
+            25 => Self::RevokeAttestation,
+            26 => Self::AddCurator,
+            27 => Self::RemoveCurator,
+            28 => Self::ApproveProject,
+            29 => Self::RejectProject,
+            31 => Self::SetRoundAuthority,
+            32 => Self::AcceptRoundAuthority,
+            33 => Self::InitConfig,
+            34 => Self::AddCreator,
+            35 => Self::RemoveCreator,
+            36 => Self::CloseRound,
+            37 => Self::CloseVoter,
+            38 => Self::CloseProject,
+            39 => Self::DistributeToProject,
+            40 => Self::ClaimVested,
+            41 => Self::ClawBack,
+            42 => Self::RollOver,
+            43 => Self::CloseDonor,
             _ => return Err(ProgramError::InvalidInstructionData),
         })
     }
@@ -50,13 +223,45 @@ impl QFInstruction {
     pub fn pack(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(size_of::<Self>());
         match self {
-            Self::StartRound => buf.push(0),
+            &Self::StartRound(RoundConfig {
+                start_time,
+                end_time,
+                registration_end_time,
+                fee_bps,
+                fee_policy,
+                matching_cap_bps,
+                matching_algorithm,
+                pairwise_threshold,
+                identity_issuer,
+                curated,
+                empty_round_policy,
+                vesting_cliff,
+                vesting_duration,
+            }) => {
+                buf.push(0);
+                buf.extend_from_slice(&start_time.to_le_bytes());
+                buf.extend_from_slice(&end_time.to_le_bytes());
+                buf.extend_from_slice(&registration_end_time.to_le_bytes());
+                buf.extend_from_slice(&fee_bps.to_le_bytes());
+                buf.push(fee_policy as u8);
+                buf.extend_from_slice(&matching_cap_bps.to_le_bytes());
+                buf.push(matching_algorithm as u8);
+                buf.extend_from_slice(&pairwise_threshold.to_le_bytes());
+                buf.extend_from_slice(identity_issuer.as_ref());
+                buf.push(curated as u8);
+                buf.push(empty_round_policy as u8);
+                buf.extend_from_slice(&vesting_cliff.to_le_bytes());
+                buf.extend_from_slice(&vesting_duration.to_le_bytes());
+            }
             &Self::Donate { amount, decimals } => {
                 buf.push(1);
                 buf.extend_from_slice(&amount.to_le_bytes());
                 buf.push(decimals);
             }
-            Self::RegisterProject => buf.push(2),
+            Self::RegisterProject(metadata) => {
+                buf.push(2);
+                buf.extend_from_slice(&metadata.pack());
+            }
             Self::InitVoter => buf.push(3),
             &Self::Vote { amount, decimals } => {
                 buf.push(4);
@@ -66,7 +271,1434 @@ impl QFInstruction {
             Self::Withdraw => buf.push(5),
             Self::EndRound => buf.push(6),
             Self::WithdrawFee => buf.push(7),
+            Self::FinalizeRound => buf.push(8),
+            Self::SettleMatching => buf.push(9),
+            Self::InitVoterPair => buf.push(10),
+            Self::AccumulateVoterPair => buf.push(11),
+            Self::DistributeVoterPair => buf.push(12),
+            Self::CancelRound => buf.push(13),
+            Self::RefundDonation => buf.push(14),
+            Self::RefundVote => buf.push(15),
+            &Self::InitPriceFeed { price } => {
+                buf.push(16);
+                buf.extend_from_slice(&price.to_le_bytes());
+            }
+            &Self::SetPrice { price } => {
+                buf.push(17);
+                buf.extend_from_slice(&price.to_le_bytes());
+            }
+            Self::AddMint => buf.push(18),
+            Self::WithdrawMint => buf.push(19),
+            Self::WithdrawMintFee => buf.push(20),
+            &Self::DonateNative { amount } => {
+                buf.push(21);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            &Self::VoteNative { amount } => {
+                buf.push(22);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            &Self::Attest { trust_bps } => {
+                buf.push(24);
+                buf.extend_from_slice(&trust_bps.to_le_bytes());
+            }
+            Self::RevokeAttestation => buf.push(25),
+            Self::AddCurator => buf.push(26),
+            Self::RemoveCurator => buf.push(27),
+            Self::ApproveProject => buf.push(28),
+            Self::RejectProject => buf.push(29),
+            Self::UpdateProjectMetadata(metadata) => {
+                buf.push(30);
+                buf.extend_from_slice(&metadata.pack());
+            }
+            Self::SetRoundAuthority => buf.push(31),
+            Self::AcceptRoundAuthority => buf.push(32),
+            Self::InitConfig => buf.push(33),
+            Self::AddCreator => buf.push(34),
+            Self::RemoveCreator => buf.push(35),
+            Self::CloseRound => buf.push(36),
+            Self::CloseVoter => buf.push(37),
+            Self::CloseProject => buf.push(38),
+            Self::DistributeToProject => buf.push(39),
+            Self::ClaimVested => buf.push(40),
+            Self::ClawBack => buf.push(41),
+            Self::RollOver => buf.push(42),
+            Self::CloseDonor => buf.push(43),
         };
         buf
     }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() < 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (key, rest) = input.split_at(32);
+        let key = key
+            .try_into()
+            .ok()
+            .map(Pubkey::new_from_array)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((key, rest))
+    }
+
+    fn unpack_u16(input: &[u8]) -> Result<(u16, &[u8]), ProgramError> {
+        if input.len() < 2 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (value, rest) = input.split_at(2);
+        let value = value
+            .try_into()
+            .ok()
+            .map(u16::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, rest))
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        if input.len() < 8 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (value, rest) = input.split_at(8);
+        let value = value
+            .try_into()
+            .ok()
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, rest))
+    }
+
+    fn unpack_i64(input: &[u8]) -> Result<(i64, &[u8]), ProgramError> {
+        if input.len() < 8 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (value, rest) = input.split_at(8);
+        let value = value
+            .try_into()
+            .ok()
+            .map(i64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, rest))
+    }
+}
+
+/// Derives the owner of the round vault
+pub fn get_vault_owner_address(program_id: &Pubkey, round: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[&round.to_bytes()], program_id).0
+}
+
+/// Derives the round vault, the associated token account of the vault owner
+pub fn get_vault_address(program_id: &Pubkey, round: &Pubkey, mint: &Pubkey) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address(
+        &get_vault_owner_address(program_id, round),
+        mint,
+    )
+}
+
+/// Derives the donor account of a donor wallet in a round for one mint
+pub fn get_donor_address(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"donor",
+            &round.to_bytes(),
+            &authority.to_bytes(),
+            &mint.to_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+/// Derives the round mint account of another mint accepted by a round
+pub fn get_round_mint_address(program_id: &Pubkey, round: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"round_mint", &round.to_bytes(), &mint.to_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Derives the withdraw receipt of a project for another mint of its round
+pub fn get_withdraw_receipt_address(
+    program_id: &Pubkey,
+    project: &Pubkey,
+    mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"receipt", &project.to_bytes(), &mint.to_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Derives the voter account of a voter wallet on a project
+pub fn get_voter_address(program_id: &Pubkey, project: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"voter", &project.to_bytes(), &wallet.to_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Derives the attestation account of an identity issuer for a wallet
+pub fn get_attestation_address(program_id: &Pubkey, issuer: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"attestation", &issuer.to_bytes(), &wallet.to_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Derives the program wide config account
+pub fn get_config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], program_id).0
+}
+
+/// Derives the creator account of a wallet allowed to start rounds
+pub fn get_creator_address(program_id: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"creator", &wallet.to_bytes()], program_id).0
+}
+
+/// Derives the curator account of a wallet in a round
+pub fn get_curator_address(program_id: &Pubkey, round: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"curator", &round.to_bytes(), &wallet.to_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Derives the voter pair account of two voter wallets in a round,
+/// `voter_a` must be less than `voter_b`
+pub fn get_voter_pair_address(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    voter_a: &Pubkey,
+    voter_b: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"voter_pair",
+            &round.to_bytes(),
+            &voter_a.to_bytes(),
+            &voter_b.to_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+/// Creates a `StartRound` instruction, the round account has to be created
+/// and assigned to the program beforehand. The owner signs, and has to be an
+/// allowed creator once the program config exists.
+pub fn start_round(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    funder: &Pubkey,
+    mint: &Pubkey,
+    fee_recipient: &Pubkey,
+    config: RoundConfig,
+) -> Instruction {
+    let vault_owner = get_vault_owner_address(program_id, round);
+    let vault = get_vault_address(program_id, round, mint);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new(*funder, true),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(vault_owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(*fee_recipient, false),
+            AccountMeta::new_readonly(get_config_address(program_id), false),
+            AccountMeta::new_readonly(get_creator_address(program_id, owner), false),
+        ],
+        data: QFInstruction::StartRound(config).pack(),
+    }
+}
+
+/// Creates a `Donate` instruction, `authority` pays for the donor account
+pub fn donate(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    from: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(*from, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new(get_donor_address(program_id, round, authority, mint), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::Donate { amount, decimals }.pack(),
+    }
+}
+
+/// Creates a `RegisterProject` instruction, the project account has to be
+/// created with `Project::account_len(metadata)` and assigned to the program
+/// beforehand. `payer` gets its rent back on `CloseProject`.
+pub fn register_project(
+    program_id: &Pubkey,
+    project: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    metadata: &ProjectMetadata,
+) -> Instruction {
+    let mut instruction = Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*project, false),
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(*payer, true),
+        ],
+        data: QFInstruction::RegisterProject(metadata.clone()).pack(),
+    };
+    push_payout_account(&mut instruction, metadata);
+    instruction
+}
+
+/// Appends the payout account of `metadata` when it is set, it is checked to
+/// be a token account of the round mint
+fn push_payout_account(instruction: &mut Instruction, metadata: &ProjectMetadata) {
+    if metadata.payout != Pubkey::default() {
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(metadata.payout, false));
+    }
+}
+
+/// Creates an `InitVoter` instruction, the voter wallet has to sign
+pub fn init_voter(
+    program_id: &Pubkey,
+    project: &Pubkey,
+    wallet: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(get_voter_address(program_id, project, wallet), false),
+            AccountMeta::new_readonly(*wallet, true),
+            AccountMeta::new_readonly(*project, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::InitVoter.pack(),
+    }
+}
+
+/// Creates a `Vote` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn vote(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    from: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new(get_voter_address(program_id, project, authority), false),
+            AccountMeta::new(*from, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: QFInstruction::Vote { amount, decimals }.pack(),
+    }
+}
+
+/// Creates a `Withdraw` instruction
+pub fn withdraw(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    mint: &Pubkey,
+    project: &Pubkey,
+    project_owner: &Pubkey,
+    to: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new_readonly(*project_owner, true),
+            AccountMeta::new(*to, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: QFInstruction::Withdraw.pack(),
+    }
+}
+
+/// Creates an `EndRound` instruction
+pub fn end_round(program_id: &Pubkey, round: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: QFInstruction::EndRound.pack(),
+    }
+}
+
+/// Creates a `WithdrawFee` instruction, `fee_recipient` is the fee recipient
+/// token account of the round
+pub fn withdraw_fee(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    fee_recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: QFInstruction::WithdrawFee.pack(),
+    }
+}
+
+/// Creates a `FinalizeRound` instruction
+pub fn finalize_round(program_id: &Pubkey, round: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: QFInstruction::FinalizeRound.pack(),
+    }
+}
+
+/// Creates a `SettleMatching` instruction for the next `projects` of the
+/// round in the order of `payout::settlement_order`. A round with many
+/// projects settles over several instructions, until every project is
+/// settled. The signers of a multisig owner are added after the projects
+/// with [`with_multisig_signers`].
+pub fn settle_matching(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    projects: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*round, false),
+        AccountMeta::new_readonly(*owner, true),
+    ];
+    accounts.extend(
+        projects
+            .iter()
+            .map(|project| AccountMeta::new(*project, false)),
+    );
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: QFInstruction::SettleMatching.pack(),
+    }
+}
+
+/// Creates an `InitVoterPair` instruction, the two voter wallets can be
+/// given in any order
+pub fn init_voter_pair(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    voter_a: &Pubkey,
+    voter_b: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let (voter_a, voter_b) = if voter_a < voter_b {
+        (voter_a, voter_b)
+    } else {
+        (voter_b, voter_a)
+    };
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(
+                get_voter_pair_address(program_id, round, voter_a, voter_b),
+                false,
+            ),
+            AccountMeta::new_readonly(*round, false),
+            AccountMeta::new_readonly(*voter_a, false),
+            AccountMeta::new_readonly(*voter_b, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::InitVoterPair.pack(),
+    }
+}
+
+/// Creates an `AccumulateVoterPair` instruction, the two voter wallets can be
+/// given in any order
+pub fn accumulate_voter_pair(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    voter_a: &Pubkey,
+    voter_b: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: voter_pair_accounts(program_id, round, project, voter_a, voter_b),
+        data: QFInstruction::AccumulateVoterPair.pack(),
+    }
+}
+
+/// Creates a `DistributeVoterPair` instruction, the two voter wallets can be
+/// given in any order
+pub fn distribute_voter_pair(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    voter_a: &Pubkey,
+    voter_b: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: voter_pair_accounts(program_id, round, project, voter_a, voter_b),
+        data: QFInstruction::DistributeVoterPair.pack(),
+    }
+}
+
+fn voter_pair_accounts(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    voter_a: &Pubkey,
+    voter_b: &Pubkey,
+) -> Vec<AccountMeta> {
+    let (voter_a, voter_b) = if voter_a < voter_b {
+        (voter_a, voter_b)
+    } else {
+        (voter_b, voter_a)
+    };
+    vec![
+        AccountMeta::new(*round, false),
+        AccountMeta::new(
+            get_voter_pair_address(program_id, round, voter_a, voter_b),
+            false,
+        ),
+        AccountMeta::new(*project, false),
+        AccountMeta::new_readonly(get_voter_address(program_id, project, voter_a), false),
+        AccountMeta::new_readonly(get_voter_address(program_id, project, voter_b), false),
+    ]
+}
+
+/// Creates a `CancelRound` instruction
+pub fn cancel_round(program_id: &Pubkey, round: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: QFInstruction::CancelRound.pack(),
+    }
+}
+
+/// Creates a `RefundDonation` instruction, `to` is a token account owned by
+/// the donor wallet. The round mint account is only used when `mint` is
+/// another mint than the round mint.
+pub fn refund_donation(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    to: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(get_donor_address(program_id, round, authority, mint), false),
+            AccountMeta::new(*to, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+        ],
+        data: QFInstruction::RefundDonation.pack(),
+    }
+}
+
+/// Creates a `RefundVote` instruction, the votes go back to `to`, a token
+/// account of `mint` owned by the voter wallet. The round mint account is
+/// only used when `mint` is another mint than the round mint.
+pub fn refund_vote(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    mint: &Pubkey,
+    project: &Pubkey,
+    wallet: &Pubkey,
+    to: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new(get_voter_address(program_id, project, wallet), false),
+            AccountMeta::new(*to, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+        ],
+        data: QFInstruction::RefundVote.pack(),
+    }
+}
+
+/// Creates an `InitPriceFeed` instruction, the price feed account has to be
+/// created and assigned to the program beforehand.
+pub fn init_price_feed(
+    program_id: &Pubkey,
+    price_feed: &Pubkey,
+    authority: &Pubkey,
+    price: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*price_feed, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::InitPriceFeed { price }.pack(),
+    }
+}
+
+/// Creates a `SetPrice` instruction
+pub fn set_price(
+    program_id: &Pubkey,
+    price_feed: &Pubkey,
+    authority: &Pubkey,
+    price: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*price_feed, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data: QFInstruction::SetPrice { price }.pack(),
+    }
+}
+
+/// Creates an `AddMint` instruction, `fee_recipient` is a token account of
+/// `mint` receiving the fee charged in it
+pub fn add_mint(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    price_feed: &Pubkey,
+    fee_recipient: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new_readonly(*price_feed, false),
+            AccountMeta::new_readonly(*fee_recipient, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::AddMint.pack(),
+    }
+}
+
+/// Creates a `Donate` instruction in another mint accepted by the round
+#[allow(clippy::too_many_arguments)]
+pub fn donate_in_mint(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    from: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    price_feed: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Instruction {
+    let mut instruction = donate(program_id, round, from, mint, authority, amount, decimals);
+    instruction.accounts.extend([
+        AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+        AccountMeta::new_readonly(*price_feed, false),
+    ]);
+    instruction
+}
+
+/// Creates a `Vote` instruction in another mint accepted by the round
+#[allow(clippy::too_many_arguments)]
+pub fn vote_in_mint(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    from: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    price_feed: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Instruction {
+    let mut instruction = vote(
+        program_id, round, project, from, mint, authority, amount, decimals,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+        AccountMeta::new_readonly(*price_feed, false),
+    ]);
+    instruction
+}
+
+/// Creates a `WithdrawMint` instruction, the project owner pays for the
+/// withdraw receipt. `payout` is the payout token account in the metadata of
+/// the project when it has one, `to` has to be owned by its owner.
+pub fn withdraw_mint(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    mint: &Pubkey,
+    project: &Pubkey,
+    project_owner: &Pubkey,
+    to: &Pubkey,
+    payout: Option<&Pubkey>,
+) -> Instruction {
+    let mut instruction = Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*round, false),
+            AccountMeta::new_readonly(*project, false),
+            AccountMeta::new(*project_owner, true),
+            AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*to, false),
+            AccountMeta::new(
+                get_withdraw_receipt_address(program_id, project, mint),
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::WithdrawMint.pack(),
+    };
+    if let Some(payout) = payout {
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(*payout, false));
+    }
+    instruction
+}
+
+/// Creates a `WithdrawMintFee` instruction, `fee_recipient` is the fee
+/// recipient token account of the round mint account
+pub fn withdraw_mint_fee(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    fee_recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: QFInstruction::WithdrawMintFee.pack(),
+    }
+}
+
+/// Creates a `DonateNative` instruction, `amount` lamports of the authority
+/// are wrapped into the wrapped SOL vault of the round
+pub fn donate_native(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mint = native_mint::id();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(get_vault_address(program_id, round, &mint), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new(
+                get_donor_address(program_id, round, authority, &mint),
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::DonateNative { amount }.pack(),
+    }
+}
+
+/// Creates a `DonateNative` instruction in a round which accepts wrapped SOL
+/// as another mint
+pub fn donate_native_in_mint(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    authority: &Pubkey,
+    price_feed: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = donate_native(program_id, round, authority, amount);
+    instruction.accounts.extend([
+        AccountMeta::new(
+            get_round_mint_address(program_id, round, &native_mint::id()),
+            false,
+        ),
+        AccountMeta::new_readonly(*price_feed, false),
+    ]);
+    instruction
+}
+
+/// Creates a `VoteNative` instruction, the voter account is the one of the
+/// authority wallet
+pub fn vote_native(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new(get_voter_address(program_id, project, authority), false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(
+                get_vault_address(program_id, round, &native_mint::id()),
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: QFInstruction::VoteNative { amount }.pack(),
+    }
+}
+
+/// Creates a `VoteNative` instruction in a round which accepts wrapped SOL as
+/// another mint
+pub fn vote_native_in_mint(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    authority: &Pubkey,
+    price_feed: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = vote_native(program_id, round, project, authority, amount);
+    instruction.accounts.extend([
+        AccountMeta::new(
+            get_round_mint_address(program_id, round, &native_mint::id()),
+            false,
+        ),
+        AccountMeta::new_readonly(*price_feed, false),
+    ]);
+    instruction
+}
+
+/// Creates an `Attest` instruction, attesting `wallet` as a unique person
+/// trusted by `trust_bps`. Attesting a wallet again updates its trust score.
+pub fn attest(
+    program_id: &Pubkey,
+    issuer: &Pubkey,
+    wallet: &Pubkey,
+    payer: &Pubkey,
+    trust_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(get_attestation_address(program_id, issuer, wallet), false),
+            AccountMeta::new_readonly(*issuer, true),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::Attest { trust_bps }.pack(),
+    }
+}
+
+/// Creates a `RevokeAttestation` instruction, the rent of the attestation
+/// account goes to `recipient`
+pub fn revoke_attestation(
+    program_id: &Pubkey,
+    issuer: &Pubkey,
+    wallet: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(get_attestation_address(program_id, issuer, wallet), false),
+            AccountMeta::new_readonly(*issuer, true),
+            AccountMeta::new(*recipient, false),
+        ],
+        data: QFInstruction::RevokeAttestation.pack(),
+    }
+}
+
+/// Appends the attestation account of `wallet` to a `Vote` or `VoteNative`
+/// instruction in a round gated by `issuer`
+pub fn with_attestation(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    issuer: &Pubkey,
+    wallet: &Pubkey,
+) -> Instruction {
+    instruction.accounts.push(AccountMeta::new_readonly(
+        get_attestation_address(program_id, issuer, wallet),
+        false,
+    ));
+    instruction
+}
+
+/// Creates an `AddCurator` instruction, letting `wallet` review the projects
+/// of the round
+pub fn add_curator(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    wallet: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_curator_address(program_id, round, wallet), false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::AddCurator.pack(),
+    }
+}
+
+/// Creates a `RemoveCurator` instruction, the rent of the curator account goes
+/// to `recipient`
+pub fn remove_curator(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    wallet: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_curator_address(program_id, round, wallet), false),
+            AccountMeta::new(*recipient, false),
+        ],
+        data: QFInstruction::RemoveCurator.pack(),
+    }
+}
+
+/// Creates an `ApproveProject` instruction, `authority` is the round owner or
+/// a curator of the round
+pub fn approve_project(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: review_project_accounts(program_id, round, project, authority),
+        data: QFInstruction::ApproveProject.pack(),
+    }
+}
+
+/// Creates a `RejectProject` instruction, `authority` is the round owner or a
+/// curator of the round
+pub fn reject_project(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: review_project_accounts(program_id, round, project, authority),
+        data: QFInstruction::RejectProject.pack(),
+    }
+}
+
+fn review_project_accounts(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    authority: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*round, false),
+        AccountMeta::new(*project, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(get_curator_address(program_id, round, authority), false),
+    ]
+}
+
+/// Creates an `UpdateProjectMetadata` instruction, the owner pays the rent of
+/// larger metadata
+pub fn update_project_metadata(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    owner: &Pubkey,
+    metadata: &ProjectMetadata,
+) -> Instruction {
+    let mut instruction = Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*round, false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::UpdateProjectMetadata(metadata.clone()).pack(),
+    };
+    push_payout_account(&mut instruction, metadata);
+    instruction
+}
+
+/// Creates a `SetRoundAuthority` instruction, proposing `new_owner` as the
+/// owner of the round. `Pubkey::default()` withdraws a proposal.
+pub fn set_round_authority(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(*new_owner, false),
+        ],
+        data: QFInstruction::SetRoundAuthority.pack(),
+    }
+}
+
+/// Creates an `AcceptRoundAuthority` instruction, the proposed owner takes
+/// over the round
+pub fn accept_round_authority(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    new_owner: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*new_owner, true),
+        ],
+        data: QFInstruction::AcceptRoundAuthority.pack(),
+    }
+}
+
+/// Turns an instruction signed by a round authority into one signed by the
+/// signers of `multisig`, an SPL token multisig account which is the authority
+pub fn with_multisig_signers(
+    mut instruction: Instruction,
+    multisig: &Pubkey,
+    signers: &[&Pubkey],
+) -> Instruction {
+    for account in instruction.accounts.iter_mut() {
+        if account.pubkey == *multisig {
+            account.is_signer = false;
+        }
+    }
+    instruction.accounts.extend(
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(**signer, true)),
+    );
+    instruction
+}
+
+/// Derives the program data account of the program deployed with the
+/// upgradeable loader
+pub fn get_program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[&program_id.to_bytes()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Creates an `InitConfig` instruction, `admin` manages the round creators and
+/// has to be the upgrade authority of the program
+pub fn init_config(program_id: &Pubkey, admin: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(get_config_address(program_id), false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(get_program_data_address(program_id), false),
+        ],
+        data: QFInstruction::InitConfig.pack(),
+    }
+}
+
+/// Creates an `AddCreator` instruction, allowing `wallet` to start rounds
+pub fn add_creator(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    wallet: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(get_config_address(program_id), false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(get_creator_address(program_id, wallet), false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: QFInstruction::AddCreator.pack(),
+    }
+}
+
+/// Creates a `RemoveCreator` instruction, the rent of the creator account goes
+/// to `recipient`
+pub fn remove_creator(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    wallet: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(get_config_address(program_id), false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(get_creator_address(program_id, wallet), false),
+            AccountMeta::new(*recipient, false),
+        ],
+        data: QFInstruction::RemoveCreator.pack(),
+    }
+}
+
+/// Creates a `CloseRound` instruction, the residual of the vault goes to the
+/// `to` token account and the rent of the vault and the round to `recipient`.
+/// `mints` has every mint accepted with `AddMint` with the token account the
+/// residual of its vault goes to, their vaults and round mints are closed too.
+pub fn close_round(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    to: &Pubkey,
+    recipient: &Pubkey,
+    mints: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*round, false),
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(get_vault_address(program_id, round, mint), false),
+        AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+        AccountMeta::new(*to, false),
+        AccountMeta::new(*recipient, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    for (mint, to) in mints {
+        accounts.extend([
+            AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new(*to, false),
+        ]);
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: QFInstruction::CloseRound.pack(),
+    }
+}
+
+/// Creates a `CloseVoter` instruction, the rent of the voter account goes back
+/// to `payer`, the wallet which paid it
+pub fn close_voter(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    wallet: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(get_voter_address(program_id, project, wallet), false),
+            AccountMeta::new_readonly(*project, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new(*payer, false),
+            AccountMeta::new_readonly(*round, false),
+        ],
+        data: QFInstruction::CloseVoter.pack(),
+    }
+}
+
+/// Creates a `CloseProject` instruction, the rent of the project account goes
+/// back to `payer`, the wallet which paid it. `mints` are the other mints
+/// accepted by the round, a project which has withdrawn closes after it
+/// withdrew from each of their vaults while the round is open.
+pub fn close_project(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    project: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    mints: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*project, false),
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*payer, false),
+        AccountMeta::new_readonly(*round, false),
+    ];
+    for mint in mints {
+        accounts.extend([
+            AccountMeta::new_readonly(get_round_mint_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(
+                get_withdraw_receipt_address(program_id, project, mint),
+                false,
+            ),
+        ]);
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: QFInstruction::CloseProject.pack(),
+    }
+}
+
+/// Creates a `DistributeToProject` instruction, `payout` is the payout token
+/// account in the metadata of the project
+pub fn distribute_to_project(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    mint: &Pubkey,
+    project: &Pubkey,
+    payout: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new(*payout, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: QFInstruction::DistributeToProject.pack(),
+    }
+}
+
+/// Creates a `DistributeToProject` instruction for the share of a project in
+/// the vault of another mint of the round. `to` is a token account of `mint`
+/// owned by the owner of `payout`, the payout token account in the metadata
+/// of the project, and `payer` pays for the withdraw receipt.
+pub fn distribute_to_project_mint(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    mint: &Pubkey,
+    project: &Pubkey,
+    to: &Pubkey,
+    payout: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let mut instruction = distribute_to_project(program_id, round, mint, project, to);
+    instruction.accounts.extend([
+        AccountMeta::new(get_round_mint_address(program_id, round, mint), false),
+        AccountMeta::new(
+            get_withdraw_receipt_address(program_id, project, mint),
+            false,
+        ),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(*payout, false),
+    ]);
+    instruction
+}
+
+/// Creates a `ClaimVested` instruction
+pub fn claim_vested(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    mint: &Pubkey,
+    project: &Pubkey,
+    project_owner: &Pubkey,
+    to: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new_readonly(*project_owner, true),
+            AccountMeta::new(*to, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: QFInstruction::ClaimVested.pack(),
+    }
+}
+
+/// Creates a `ClawBack` instruction, the unvested escrow of the project goes
+/// to the `to` token account
+pub fn claw_back(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    project: &Pubkey,
+    to: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*project, false),
+            AccountMeta::new(*to, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: QFInstruction::ClawBack.pack(),
+    }
+}
+
+/// Creates a `RollOver` instruction, moving the pool of an empty round into
+/// the vault of `successor`, an ongoing round of the same `mint`
+pub fn roll_over(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    successor: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*round, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_vault_address(program_id, round, mint), false),
+            AccountMeta::new_readonly(get_vault_owner_address(program_id, round), false),
+            AccountMeta::new(*successor, false),
+            AccountMeta::new(get_vault_address(program_id, successor, mint), false),
+            AccountMeta::new(get_donor_address(program_id, successor, owner, mint), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: QFInstruction::RollOver.pack(),
+    }
+}
+
+/// Creates a `CloseDonor` instruction, the rent of the donor account goes to
+/// `recipient`. A multisig `authority` takes its signers with
+/// [`with_multisig_signers`].
+pub fn close_donor(
+    program_id: &Pubkey,
+    round: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(get_donor_address(program_id, round, authority, mint), false),
+            AccountMeta::new_readonly(*round, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*recipient, false),
+        ],
+        data: QFInstruction::CloseDonor.pack(),
+    }
 }
@@ -1,5 +1,6 @@
 pub mod error;
 pub mod instruction;
+pub mod payout;
 pub mod processor;
 pub mod state;
 
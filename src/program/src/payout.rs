@@ -0,0 +1,519 @@
+//! Payout arithmetic shared by the processor and off-chain simulation
+
+use crate::{
+    error::QFError,
+    state::{
+        EmptyRoundPolicy, FeePolicy, MatchingAlgorithm, Project, ProjectStatus, Round,
+        BPS_DENOMINATOR, PRICE_SCALE,
+    },
+};
+use solana_program::clock::UnixTimestamp;
+use spl_math::uint::U256;
+use std::convert::TryFrom;
+
+/// What a project receives on withdraw and the fee charged on it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Payout {
+    pub amount: u64,
+    pub fee: u64,
+}
+
+/// Area of the project and of the round under the matching algorithm of the round
+pub fn matching_areas(round: &Round, project: &Project) -> (U256, U256) {
+    match round.matching_algorithm {
+        MatchingAlgorithm::Quadratic => (project.area, round.area),
+        MatchingAlgorithm::Pairwise => (project.pairwise_area, round.pairwise_area),
+    }
+}
+
+/// Whether no vote of the round is matched, its matching pool is then handed
+/// out by the empty round policy of the round
+pub fn is_empty_round(round: &Round) -> bool {
+    match round.matching_algorithm {
+        MatchingAlgorithm::Quadratic => round.area.is_zero(),
+        MatchingAlgorithm::Pairwise => round.pairwise_area.is_zero(),
+    }
+}
+
+/// Most matching fund a project can receive in a round with a matching cap
+pub fn matching_cap(round: &Round) -> Result<u64, QFError> {
+    u64::try_from(
+        U256::from(round.fund)
+            .checked_mul(U256::from(round.matching_cap_bps))
+            .ok_or(QFError::MathOverflow)?
+            .checked_div(U256::from(BPS_DENOMINATOR))
+            .ok_or(QFError::MathOverflow)?,
+    )
+    .map_err(|_| QFError::MathOverflow)
+}
+
+/// Empty round policy the round follows. A round which would split its pool
+/// equally among no approved project refunds its donors instead, the pool
+/// would otherwise stay in the vault until `CloseRound` sweeps it to the owner.
+pub fn empty_round_policy(round: &Round) -> EmptyRoundPolicy {
+    if round.empty_round_policy == EmptyRoundPolicy::SplitEqually && round.approved_count == 0 {
+        EmptyRoundPolicy::RefundDonors
+    } else {
+        round.empty_round_policy
+    }
+}
+
+/// Matching fund of a project in an empty round, see [`is_empty_round`]
+pub fn empty_round_matching(round: &Round, project: &Project) -> Result<u64, QFError> {
+    if empty_round_policy(round) != EmptyRoundPolicy::SplitEqually
+        || project.status != ProjectStatus::Approved
+    {
+        return Ok(0);
+    }
+    let matched = round
+        .fund
+        .checked_div(round.approved_count)
+        .ok_or(QFError::MathOverflow)?;
+    if round.matching_cap_bps == 0 {
+        Ok(matched)
+    } else {
+        Ok(matched.min(matching_cap(round)?))
+    }
+}
+
+/// Matching fund of a project in a round without a matching cap
+pub fn uncapped_matching(round: &Round, project: &Project) -> Result<u64, QFError> {
+    if is_empty_round(round) {
+        return empty_round_matching(round, project);
+    }
+    let (project_area, round_area) = matching_areas(round, project);
+    u64::try_from(
+        U256::from(round.fund)
+            .checked_mul(project_area)
+            .ok_or(QFError::MathOverflow)?
+            .checked_div(round_area)
+            .ok_or(QFError::MathOverflow)?,
+    )
+    .map_err(|_| QFError::MathOverflow)
+}
+
+/// Splits `fund` by area with no project getting more than `cap`, the excess
+/// of capped projects is redistributed pro rata among the uncapped ones.
+pub fn capped_matching(fund: u64, cap: u64, areas: &[U256]) -> Result<Vec<u64>, QFError> {
+    let mut capped = vec![false; areas.len()];
+    loop {
+        let capped_count = capped.iter().filter(|c| **c).count() as u64;
+        let remaining_fund = U256::from(
+            fund.checked_sub(cap.checked_mul(capped_count).ok_or(QFError::MathOverflow)?)
+                .ok_or(QFError::MathOverflow)?,
+        );
+        let remaining_area = areas
+            .iter()
+            .zip(capped.iter())
+            .filter(|(_, c)| !**c)
+            .try_fold(U256::zero(), |acc, (area, _)| acc.checked_add(*area))
+            .ok_or(QFError::MathOverflow)?;
+
+        let mut changed = false;
+        for (area, capped) in areas.iter().zip(capped.iter_mut()) {
+            if !*capped
+                && area
+                    .checked_mul(remaining_fund)
+                    .ok_or(QFError::MathOverflow)?
+                    > U256::from(cap)
+                        .checked_mul(remaining_area)
+                        .ok_or(QFError::MathOverflow)?
+            {
+                *capped = true;
+                changed = true;
+            }
+        }
+        if changed {
+            continue;
+        }
+
+        return areas
+            .iter()
+            .zip(capped.iter())
+            .map(|(area, capped)| {
+                if *capped {
+                    Ok(cap)
+                } else if remaining_area.is_zero() {
+                    Ok(0)
+                } else {
+                    u64::try_from(
+                        remaining_fund
+                            .checked_mul(*area)
+                            .ok_or(QFError::MathOverflow)?
+                            .checked_div(remaining_area)
+                            .ok_or(QFError::MathOverflow)?,
+                    )
+                    .map_err(|_| QFError::MathOverflow)
+                }
+            })
+            .collect();
+    }
+}
+
+/// Order in which `SettleMatching` takes the projects of a round, by matching
+/// area descending then by index. Returns indices into `projects`.
+pub fn settlement_order(round: &Round, projects: &[Project]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..projects.len()).collect();
+    order.sort_by(|a, b| {
+        let (area_a, _) = matching_areas(round, &projects[*a]);
+        let (area_b, _) = matching_areas(round, &projects[*b]);
+        area_b
+            .cmp(&area_a)
+            .then(projects[*a].index.cmp(&projects[*b].index))
+    });
+    order
+}
+
+/// Matching fund of the next project in settlement order of a round with a
+/// matching cap, as `SettleMatching` records it. Projects are capped while
+/// they would get more than the cap from what the capped ones left, which
+/// gives the same split as [`capped_matching`] one project at a time.
+///
+/// A project which does not follow the last settled one starts the
+/// settlement over from it. The matching of the round is settled once
+/// `project_count` projects are settled in a row, which takes every project
+/// in settlement order.
+pub fn settle_next(round: &mut Round, project: &Project) -> Result<u64, QFError> {
+    if round.matching_settled {
+        return Err(QFError::MatchingAlreadySettled);
+    }
+    let (project_area, round_area) = matching_areas(round, project);
+    if round.settled_count != 0
+        && (project_area > round.last_settled_area
+            || (project_area == round.last_settled_area
+                && project.index <= round.last_settled_index))
+    {
+        round.settled_count = 0;
+        round.capped_count = 0;
+        round.capped_area = U256::zero();
+    }
+
+    let matched = if is_empty_round(round) {
+        empty_round_matching(round, project)?
+    } else {
+        let cap = matching_cap(round)?;
+        let remaining_fund = U256::from(
+            round
+                .fund
+                .checked_sub(
+                    cap.checked_mul(round.capped_count)
+                        .ok_or(QFError::MathOverflow)?,
+                )
+                .ok_or(QFError::MathOverflow)?,
+        );
+        let remaining_area = round_area
+            .checked_sub(round.capped_area)
+            .ok_or(QFError::MathOverflow)?;
+        if round.capped_count == round.settled_count
+            && project_area
+                .checked_mul(remaining_fund)
+                .ok_or(QFError::MathOverflow)?
+                > U256::from(cap)
+                    .checked_mul(remaining_area)
+                    .ok_or(QFError::MathOverflow)?
+        {
+            round.capped_count = round
+                .capped_count
+                .checked_add(1)
+                .ok_or(QFError::MathOverflow)?;
+            round.capped_area = round
+                .capped_area
+                .checked_add(project_area)
+                .ok_or(QFError::MathOverflow)?;
+            cap
+        } else if remaining_area.is_zero() {
+            0
+        } else {
+            u64::try_from(
+                remaining_fund
+                    .checked_mul(project_area)
+                    .ok_or(QFError::MathOverflow)?
+                    .checked_div(remaining_area)
+                    .ok_or(QFError::MathOverflow)?,
+            )
+            .map_err(|_| QFError::MathOverflow)?
+        }
+    };
+
+    round.settled_count = round
+        .settled_count
+        .checked_add(1)
+        .ok_or(QFError::MathOverflow)?;
+    round.last_settled_area = project_area;
+    round.last_settled_index = project.index;
+    if round.settled_count == round.project_count {
+        round.matching_settled = true;
+    }
+    Ok(matched)
+}
+
+/// Matching fund of every project of a round with a matching cap, as
+/// `SettleMatching` records it. `projects` has to contain every project of
+/// the round.
+pub fn settled_matching(round: &Round, projects: &[Project]) -> Result<Vec<u64>, QFError> {
+    let mut round = Round {
+        matching_settled: false,
+        settled_count: 0,
+        capped_count: 0,
+        capped_area: U256::zero(),
+        ..*round
+    };
+    let mut matched = vec![0; projects.len()];
+    for i in settlement_order(&round, projects) {
+        matched[i] = settle_next(&mut round, &projects[i])?;
+    }
+    Ok(matched)
+}
+
+/// Payout of a project which gets `matched` from the matching pool
+pub fn project_payout(round: &Round, project: &Project, matched: u64) -> Result<Payout, QFError> {
+    let fee_base = match round.fee_policy {
+        FeePolicy::Total => project
+            .votes
+            .checked_add(matched)
+            .ok_or(QFError::MathOverflow)?,
+        FeePolicy::MatchingOnly => matched,
+        FeePolicy::ContributionsOnly => project.votes,
+    };
+    let fee = u64::try_from(
+        U256::from(fee_base)
+            .checked_mul(U256::from(round.fee_bps))
+            .ok_or(QFError::MathOverflow)?
+            .checked_div(U256::from(BPS_DENOMINATOR))
+            .ok_or(QFError::MathOverflow)?,
+    )
+    .map_err(|_| QFError::MathOverflow)?;
+    let amount = project
+        .votes
+        .checked_add(matched)
+        .ok_or(QFError::MathOverflow)?
+        .checked_sub(fee)
+        .ok_or(QFError::MathOverflow)?;
+
+    Ok(Payout { amount, fee })
+}
+
+/// Part of the payout of a project which goes into its vesting escrow, the
+/// matching fund after its share of the fee. Zero in a round without vesting.
+pub fn escrowed_payout(round: &Round, project: &Project, payout: &Payout) -> Result<u64, QFError> {
+    if !round.is_vesting() {
+        return Ok(0);
+    }
+    let votes_fee = match round.fee_policy {
+        FeePolicy::Total => u64::try_from(
+            U256::from(project.votes)
+                .checked_mul(U256::from(round.fee_bps))
+                .ok_or(QFError::MathOverflow)?
+                .checked_div(U256::from(BPS_DENOMINATOR))
+                .ok_or(QFError::MathOverflow)?,
+        )
+        .map_err(|_| QFError::MathOverflow)?,
+        FeePolicy::MatchingOnly => 0,
+        FeePolicy::ContributionsOnly => payout.fee,
+    };
+    let direct = project
+        .votes
+        .checked_sub(votes_fee)
+        .ok_or(QFError::MathOverflow)?;
+    Ok(payout.amount.saturating_sub(direct))
+}
+
+/// Part of the escrow of a project vested at `now`. Vesting starts when the
+/// round finished, which is before its end time if the owner ended it early,
+/// nothing vests before the cliff and everything after the duration.
+pub fn vested_payout(round: &Round, project: &Project, now: UnixTimestamp) -> Result<u64, QFError> {
+    let elapsed = now.saturating_sub(round.finished_time);
+    if project.flagged || elapsed >= round.vesting_duration {
+        Ok(project.escrowed)
+    } else if elapsed < round.vesting_cliff {
+        Ok(0)
+    } else {
+        u64::try_from(
+            U256::from(project.escrowed)
+                .checked_mul(U256::from(elapsed))
+                .ok_or(QFError::MathOverflow)?
+                .checked_div(U256::from(round.vesting_duration))
+                .ok_or(QFError::MathOverflow)?,
+        )
+        .map_err(|_| QFError::MathOverflow)
+    }
+}
+
+/// Value of `amount` of a mint in units of the round mint at `price`
+pub fn convert(amount: u64, price: u64) -> Result<u64, QFError> {
+    u64::try_from(
+        U256::from(amount)
+            .checked_mul(U256::from(price))
+            .ok_or(QFError::MathOverflow)?
+            .checked_div(U256::from(PRICE_SCALE))
+            .ok_or(QFError::MathOverflow)?,
+    )
+    .map_err(|_| QFError::MathOverflow)
+}
+
+/// Whether the donors of a round get their donations back with
+/// `RefundDonation` instead of funding the matching
+pub fn refunds_donors(round: &Round) -> bool {
+    is_empty_round(round) && empty_round_policy(round) == EmptyRoundPolicy::RefundDonors
+}
+
+/// Tokens a vault with `deposited` tokens, `donated` of them donations, pays
+/// for `value` of the round. Every vault pays the same share of what was
+/// deposited in it, so a round with only its own mint pays exactly `value`.
+/// Donations which go back to the donors are left out, each vault then only
+/// pays out of its votes.
+pub fn vault_share(
+    round: &Round,
+    value: u64,
+    deposited: u64,
+    donated: u64,
+) -> Result<u64, QFError> {
+    let (total_value, deposited) = if refunds_donors(round) {
+        (
+            U256::from(round.votes),
+            deposited
+                .checked_sub(donated)
+                .ok_or(QFError::MathOverflow)?,
+        )
+    } else {
+        (
+            U256::from(round.fund)
+                .checked_add(U256::from(round.votes))
+                .ok_or(QFError::MathOverflow)?,
+            deposited,
+        )
+    };
+    if total_value.is_zero() {
+        return Ok(0);
+    }
+    u64::try_from(
+        U256::from(value)
+            .checked_mul(U256::from(deposited))
+            .ok_or(QFError::MathOverflow)?
+            .checked_div(total_value)
+            .ok_or(QFError::MathOverflow)?,
+    )
+    .map_err(|_| QFError::MathOverflow)
+}
+
+/// Simulates `Withdraw` for every project of a round as if the round ended
+/// now, `projects` has to contain every project of the round.
+///
+/// The amounts are in units of the round mint, see [`vault_share`] for the
+/// tokens each vault pays. The result is exactly what the program pays out,
+/// except for a pairwise round whose voter pairs are not all distributed yet.
+pub fn simulate_payouts(round: &Round, projects: &[Project]) -> Result<Vec<Payout>, QFError> {
+    let matched: Vec<u64> = if round.matching_cap_bps == 0 {
+        projects
+            .iter()
+            .map(|project| uncapped_matching(round, project))
+            .collect::<Result<_, _>>()?
+    } else if round.matching_settled {
+        projects.iter().map(|project| project.matched).collect()
+    } else {
+        settled_matching(round, projects)?
+    };
+
+    projects
+        .iter()
+        .zip(matched)
+        .map(|(project, matched)| project_payout(round, project, matched))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capped_round(fund: u64, matching_cap_bps: u16, areas: &[u64]) -> (Round, Vec<Project>) {
+        let projects: Vec<Project> = areas
+            .iter()
+            .enumerate()
+            .map(|(index, area)| Project {
+                area: U256::from(*area),
+                index: index as u64,
+                ..Project::default()
+            })
+            .collect();
+        let round = Round {
+            fund,
+            matching_cap_bps,
+            area: U256::from(areas.iter().sum::<u64>()),
+            project_count: areas.len() as u64,
+            ..Round::default()
+        };
+        (round, projects)
+    }
+
+    #[test]
+    fn test_settle_next_matches_capped_matching() {
+        let many: Vec<u64> = (0..40).map(|i| (i * i * 37) % 1_000).collect();
+        let cases: [(u16, &[u64]); 6] = [
+            (6_000, &[900, 400, 100]),
+            (3_000, &[100, 300, 300, 300]),
+            (2_000, &[1, 500, 500, 500, 2]),
+            (5_000, &[0, 1_000, 0]),
+            (500, &many),
+            (10_000, &many),
+        ];
+        for (matching_cap_bps, areas) in cases {
+            let (round, projects) = capped_round(1_000_000, matching_cap_bps, areas);
+            let areas: Vec<U256> = projects.iter().map(|project| project.area).collect();
+            let expected =
+                capped_matching(round.fund, matching_cap(&round).unwrap(), &areas).unwrap();
+
+            let mut state = round;
+            let mut matched = vec![0; projects.len()];
+            for batch in settlement_order(&round, &projects).chunks(3) {
+                assert!(!state.matching_settled);
+                for &i in batch {
+                    matched[i] = settle_next(&mut state, &projects[i]).unwrap();
+                }
+            }
+            assert!(state.matching_settled);
+            assert_eq!(matched, expected);
+            assert_eq!(settled_matching(&round, &projects).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_settle_next_starts_over() {
+        let (round, projects) = capped_round(1_000, 6_000, &[900, 400, 100]);
+        let mut state = round;
+        settle_next(&mut state, &projects[1]).unwrap();
+        settle_next(&mut state, &projects[2]).unwrap();
+        // project 0 precedes the settled ones
+        assert_eq!(settle_next(&mut state, &projects[0]), Ok(600));
+        assert_eq!(state.settled_count, 1);
+        assert_eq!(settle_next(&mut state, &projects[1]), Ok(320));
+        assert_eq!(settle_next(&mut state, &projects[2]), Ok(80));
+        assert!(state.matching_settled);
+        assert_eq!(
+            settle_next(&mut state, &projects[0]),
+            Err(QFError::MatchingAlreadySettled)
+        );
+    }
+
+    #[test]
+    fn test_split_equally_without_approved_projects() {
+        let round = Round {
+            fund: 1_000,
+            empty_round_policy: EmptyRoundPolicy::SplitEqually,
+            ..Round::default()
+        };
+        let project = Project {
+            status: ProjectStatus::Approved,
+            ..Project::default()
+        };
+        assert_eq!(empty_round_policy(&round), EmptyRoundPolicy::RefundDonors);
+        assert_eq!(empty_round_matching(&round, &project), Ok(0));
+        assert!(refunds_donors(&round));
+
+        let round = Round {
+            approved_count: 3,
+            ..round
+        };
+        assert_eq!(empty_round_matching(&round, &project), Ok(333));
+        assert!(!refunds_donors(&round));
+    }
+}
@@ -1,21 +1,30 @@
 use crate::{
     error::QFError,
-    instruction::QFInstruction,
-    state::{Project, Round, RoundStatus, Voter},
+    instruction::{QFInstruction, RoundConfig},
+    payout::{self, Payout},
+    state::{
+        Attestation, Config, Creator, Curator, Donor, EmptyRoundPolicy, MatchingAlgorithm,
+        PriceFeed, Project, ProjectMetadata, ProjectStatus, Round, RoundMint, RoundStatus, Voter,
+        VoterPair, BPS_DENOMINATOR, MAX_FEE_BPS,
+    },
 };
 use num_traits::FromPrimitive;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     decode_error::DecodeError,
     entrypoint::ProgramResult,
     msg,
     program::{invoke, invoke_signed},
     program_error::{PrintProgramError, ProgramError},
     program_pack::{IsInitialized, Pack},
+    program_utils::limited_deserialize,
     pubkey::Pubkey,
     system_instruction, system_program,
-    sysvar::{rent::Rent, Sysvar},
+    sysvar::{clock::Clock, rent::Rent, Sysvar},
 };
+use std::slice::Iter;
+
 use spl_math::{
     precise_number::{PreciseNumber, ONE},
     uint::U256,
@@ -24,9 +33,17 @@ use spl_math::{
 use spl_associated_token_account;
 use spl_token;
 
+/// Value of a contribution in the round mint, with the round mint account of
+/// the mint it was paid in when that is not the round mint
+type Contribution<'a, 'b> = (u64, Option<(&'a AccountInfo<'b>, RoundMint)>);
+
 pub struct Processor {}
 impl Processor {
-    pub fn process_start_round(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    pub fn process_start_round(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        config: RoundConfig,
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let new_round_info = next_account_info(account_info_iter)?;
         let round_owner_info = next_account_info(account_info_iter)?;
@@ -39,6 +56,33 @@ impl Processor {
         let spl_token_program_info = next_account_info(account_info_iter)?;
         let rent_sysvar_info = next_account_info(account_info_iter)?;
         let rent = &Rent::from_account_info(rent_sysvar_info)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+        let fee_recipient_info = next_account_info(account_info_iter)?;
+        let config_info = next_account_info(account_info_iter)?;
+
+        // only allowed creators start rounds once the program config exists
+        let (expected_config, _) = Pubkey::find_program_address(&[b"config"], program_id);
+        if config_info.key != &expected_config {
+            return Err(QFError::ConfigMismatch.into());
+        }
+        if config_info.owner == program_id
+            && Config::unpack_unchecked(&config_info.data.borrow())?.is_initialized()
+        {
+            let creator_info = next_account_info(account_info_iter)?;
+            let (expected_creator, _) = Pubkey::find_program_address(
+                &[b"creator", &round_owner_info.key.to_bytes()],
+                program_id,
+            );
+            if creator_info.key != &expected_creator || creator_info.owner != program_id {
+                return Err(QFError::CreatorNotAllowed.into());
+            }
+            Creator::unpack(&creator_info.data.borrow()).or(Err(QFError::CreatorNotAllowed))?;
+        }
+        Self::check_authority(
+            round_owner_info.key,
+            round_owner_info,
+            account_info_iter.as_slice(),
+        )?;
 
         if new_round_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
@@ -54,12 +98,37 @@ impl Processor {
             return Err(ProgramError::AccountAlreadyInitialized);
         }
 
+        if config.start_time >= config.end_time
+            || config.registration_end_time > config.end_time
+            || config.end_time <= clock.unix_timestamp
+        {
+            return Err(QFError::InvalidRoundTime.into());
+        }
+
+        if config.fee_bps > MAX_FEE_BPS {
+            return Err(QFError::InvalidFeeRate.into());
+        }
+
+        if u64::from(config.matching_cap_bps) > BPS_DENOMINATOR {
+            return Err(QFError::InvalidMatchingCap.into());
+        }
+
+        if config.matching_algorithm == MatchingAlgorithm::Pairwise
+            && config.pairwise_threshold == 0
+        {
+            return Err(QFError::InvalidPairwiseThreshold.into());
+        }
+
+        if config.vesting_cliff < 0 || config.vesting_cliff > config.vesting_duration {
+            return Err(QFError::InvalidVestingSchedule.into());
+        }
+
         if associated_program_info.key != &spl_associated_token_account::ID {
             return Err(QFError::UnexpectedTokenProgramID.into());
         }
 
         let (expected_wallet, _) =
-            Pubkey::find_program_address(&[&new_round_info.key.to_bytes()], &program_id);
+            Pubkey::find_program_address(&[&new_round_info.key.to_bytes()], program_id);
         if wallet_account_info.key != &expected_wallet {
             return Err(QFError::OwnerMismatch.into());
         }
@@ -72,11 +141,20 @@ impl Processor {
             return Err(QFError::UnexpectedTokenProgramID.into());
         }
 
+        if fee_recipient_info.owner != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+        let fee_recipient = spl_token::state::Account::unpack(&fee_recipient_info.data.borrow())?;
+        if fee_recipient.mint != *spl_token_mint_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
         invoke(
-            &spl_associated_token_account::create_associated_token_account(
+            &spl_associated_token_account::instruction::create_associated_token_account(
                 funder_info.key,
                 wallet_account_info.key,
                 spl_token_mint_info.key,
+                spl_token_program_info.key,
             ),
             &[
                 associated_program_info.clone(),
@@ -95,6 +173,43 @@ impl Processor {
         round.owner = *round_owner_info.key;
         round.vault = *associated_token_account_info.key;
         round.area = U256::zero();
+        round.start_time = config.start_time;
+        round.end_time = config.end_time;
+        round.registration_end_time = config.registration_end_time;
+        round.fee_bps = config.fee_bps;
+        round.fee_policy = config.fee_policy;
+        round.matching_cap_bps = config.matching_cap_bps;
+        round.project_count = 0;
+        round.matching_settled = false;
+        round.matching_algorithm = config.matching_algorithm;
+        round.pairwise_threshold = config.pairwise_threshold;
+        round.pair_count = 0;
+        round.pairs_accumulated = 0;
+        round.pairs_distributed = 0;
+        round.pairwise_area = U256::zero();
+        round.paid_out = 0;
+        round.fee_recipient = *fee_recipient_info.key;
+        round.mint = *spl_token_mint_info.key;
+        round.votes = 0;
+        round.deposited = 0;
+        round.fee_total = 0;
+        round.identity_issuer = config.identity_issuer;
+        round.curated = config.curated;
+        round.pending_owner = Pubkey::default();
+        round.empty_round_policy = config.empty_round_policy;
+        round.approved_count = 0;
+        round.withdrawn_count = 0;
+        round.mint_count = 0;
+        round.vesting_cliff = config.vesting_cliff;
+        round.vesting_duration = config.vesting_duration;
+        round.escrowed = 0;
+        round.settled_count = 0;
+        round.capped_count = 0;
+        round.capped_area = U256::zero();
+        round.last_settled_area = U256::zero();
+        round.last_settled_index = 0;
+        round.donated = 0;
+        round.finished_time = 0;
 
         Round::pack(round, &mut new_round_info.data.borrow_mut())?;
         Ok(())
@@ -113,6 +228,10 @@ impl Processor {
         let to_info = next_account_info(account_info_iter)?;
         let from_auth_info = next_account_info(account_info_iter)?;
         let token_program_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+        let donor_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
 
         if round_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
@@ -121,23 +240,50 @@ impl Processor {
         if round.status != RoundStatus::Ongoing {
             return Err(QFError::RoundStatusError.into());
         }
-
-        if to_info.key != &round.vault {
-            return Err(QFError::VaultMismatch.into());
+        if clock.unix_timestamp < round.start_time {
+            return Err(QFError::RoundNotStarted.into());
         }
+        if clock.unix_timestamp >= round.end_time {
+            return Err(QFError::RoundEnded.into());
+        }
+
+        let (value, round_mint) = Self::unpack_contribution(
+            program_id,
+            round_info,
+            &round,
+            mint_info.key,
+            to_info,
+            account_info_iter,
+            amount,
+        )?;
 
         if token_program_info.key != &spl_token::ID {
             return Err(QFError::UnexpectedTokenProgramID.into());
         }
 
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+
+        let mut donor = Self::load_donor(
+            program_id,
+            round_info,
+            donor_info,
+            from_auth_info,
+            from_auth_info,
+            mint_info.key,
+            system_program_info,
+            rent,
+        )?;
+
         invoke(
             &spl_token::instruction::transfer_checked(
-                &token_program_info.key,
-                &from_info.key,
-                &mint_info.key,
-                &to_info.key,
-                &from_auth_info.key,
-                &[&from_auth_info.key],
+                token_program_info.key,
+                from_info.key,
+                mint_info.key,
+                to_info.key,
+                from_auth_info.key,
+                &[from_auth_info.key],
                 amount,
                 decimals,
             )?,
@@ -150,43 +296,210 @@ impl Processor {
             ],
         )?;
 
-        round.fund = round.fund.checked_add(amount).unwrap();
+        round.fund = round.fund.checked_add(value).ok_or(QFError::MathOverflow)?;
+        Self::add_deposit(&mut round, round_mint, amount, true)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        donor.amount = donor
+            .amount
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        donor.value = donor
+            .value
+            .checked_add(value)
+            .ok_or(QFError::MathOverflow)?;
+        Donor::pack(donor, &mut donor_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_donate_native(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let from_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+        let donor_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Ongoing {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if clock.unix_timestamp < round.start_time {
+            return Err(QFError::RoundNotStarted.into());
+        }
+        if clock.unix_timestamp >= round.end_time {
+            return Err(QFError::RoundEnded.into());
+        }
+
+        let (value, round_mint) = Self::unpack_contribution(
+            program_id,
+            round_info,
+            &round,
+            &spl_token::native_mint::ID,
+            to_info,
+            account_info_iter,
+            amount,
+        )?;
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+
+        let mut donor = Self::load_donor(
+            program_id,
+            round_info,
+            donor_info,
+            from_info,
+            from_info,
+            &spl_token::native_mint::ID,
+            system_program_info,
+            rent,
+        )?;
+
+        Self::wrap_lamports(
+            from_info,
+            to_info,
+            system_program_info,
+            token_program_info,
+            amount,
+        )?;
+
+        round.fund = round.fund.checked_add(value).ok_or(QFError::MathOverflow)?;
+        Self::add_deposit(&mut round, round_mint, amount, true)?;
         Round::pack(round, &mut round_info.data.borrow_mut())?;
 
+        donor.amount = donor
+            .amount
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        donor.value = donor
+            .value
+            .checked_add(value)
+            .ok_or(QFError::MathOverflow)?;
+        Donor::pack(donor, &mut donor_info.data.borrow_mut())?;
+
         Ok(())
     }
 
+    /// Loads the donor account of `authority` in `mint`, it is created on the
+    /// first donation and paid for by `payer_info`.
+    #[allow(clippy::too_many_arguments)]
+    fn load_donor<'a>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo<'a>,
+        donor_info: &AccountInfo<'a>,
+        authority_info: &AccountInfo<'a>,
+        payer_info: &AccountInfo<'a>,
+        mint: &Pubkey,
+        system_program_info: &AccountInfo<'a>,
+        rent: &Rent,
+    ) -> Result<Donor, ProgramError> {
+        let (expected_donor, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"donor",
+                &round_info.key.to_bytes(),
+                &authority_info.key.to_bytes(),
+                &mint.to_bytes(),
+            ],
+            program_id,
+        );
+        if donor_info.key != &expected_donor {
+            return Err(QFError::DonorMismatch.into());
+        }
+        if donor_info.data_is_empty() {
+            msg!("Create the donor");
+            Self::create_program_account(
+                program_id,
+                donor_info,
+                payer_info,
+                system_program_info,
+                rent,
+                Donor::LEN,
+                &[
+                    b"donor",
+                    &round_info.key.to_bytes(),
+                    &authority_info.key.to_bytes(),
+                    &mint.to_bytes(),
+                    &[bump_seed],
+                ],
+            )?;
+        }
+        if donor_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut donor = Donor::unpack_unchecked(&donor_info.data.borrow())?;
+        if !donor.is_initialized() {
+            donor.is_initialized = true;
+            donor.round = *round_info.key;
+            donor.authority = *authority_info.key;
+            donor.amount = 0;
+            donor.mint = *mint;
+            donor.value = 0;
+        }
+        Ok(donor)
+    }
+
     pub fn process_register_project(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
+        metadata: ProjectMetadata,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let new_project_info = next_account_info(account_info_iter)?;
         let round_info = next_account_info(account_info_iter)?;
         let project_owner_info = next_account_info(account_info_iter)?;
         let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+        let payer_info = next_account_info(account_info_iter)?;
 
         if round_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
-        let round = Round::unpack(&round_info.data.borrow())?;
+        let mut round = Round::unpack(&round_info.data.borrow())?;
         if round.status != RoundStatus::Ongoing {
             return Err(QFError::RoundStatusError.into());
         }
+        if clock.unix_timestamp >= round.registration_end_time {
+            return Err(QFError::RegistrationClosed.into());
+        }
 
         if new_project_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
-        let mut project = Project::unpack_unchecked(&new_project_info.data.borrow())?;
-        if project.is_initialized() {
-            return Err(ProgramError::AccountAlreadyInitialized);
+        if !metadata.is_valid() {
+            return Err(QFError::InvalidMetadata.into());
         }
-
-        if new_project_info.data_len() != Project::LEN {
+        Self::check_payout_account(&metadata, &round, account_info_iter)?;
+        // the payer gets the rent back once the project is closed
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let account_len = Project::account_len(&metadata);
+        if new_project_info.data_len() != account_len {
             return Err(ProgramError::InvalidAccountData);
         }
+        let mut project =
+            Project::unpack_unchecked(&new_project_info.data.borrow()[..Project::LEN])?;
+        if project.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
 
-        if !rent.is_exempt(new_project_info.lamports(), Project::LEN) {
+        if !rent.is_exempt(new_project_info.lamports(), account_len) {
             return Err(ProgramError::AccountNotRentExempt);
         }
 
@@ -195,8 +508,30 @@ impl Processor {
         project.withdraw = false;
         project.votes = 0;
         project.area = U256::zero();
+        project.matched = 0;
+        project.index = round.project_count;
+        project.voter_count = 0;
+        project.pairwise_area = U256::zero();
+        project.payer = *payer_info.key;
+        project.status = if round.curated {
+            ProjectStatus::Pending
+        } else {
+            round.approved_count = round
+                .approved_count
+                .checked_add(1)
+                .ok_or(QFError::MathOverflow)?;
+            ProjectStatus::Approved
+        };
 
-        Project::pack(project, &mut new_project_info.data.borrow_mut())?;
+        let mut project_data = new_project_info.data.borrow_mut();
+        Project::pack_account(project, &mut project_data)?;
+        project_data[Project::LEN..].copy_from_slice(&metadata.pack());
+
+        round.project_count = round
+            .project_count
+            .checked_add(1)
+            .ok_or(QFError::MathOverflow)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
 
         Ok(())
     }
@@ -204,7 +539,7 @@ impl Processor {
     pub fn process_init_voter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let new_voter_info = next_account_info(account_info_iter)?;
-        let voter_token_holder_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
         let project_info = next_account_info(account_info_iter)?;
         let from_info = next_account_info(account_info_iter)?;
         let system_program_info = next_account_info(account_info_iter)?;
@@ -213,54 +548,37 @@ impl Processor {
         if project_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
-        Project::unpack(&project_info.data.borrow())?;
+        Project::unpack_account(&project_info.data.borrow())?;
+
+        // one voter per wallet and project
+        if !wallet_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
 
         let (_, bump_seed) = Pubkey::find_program_address(
             &[
+                b"voter",
                 &project_info.key.to_bytes(),
-                &voter_token_holder_info.key.to_bytes(),
+                &wallet_info.key.to_bytes(),
             ],
-            &program_id,
+            program_id,
         );
         let seeds: &[&[_]] = &[
+            b"voter",
             &project_info.key.to_bytes(),
-            &voter_token_holder_info.key.to_bytes(),
+            &wallet_info.key.to_bytes(),
             &[bump_seed],
         ];
 
-        let required_lamports = rent
-            .minimum_balance(Voter::LEN)
-            .max(1)
-            .saturating_sub(new_voter_info.lamports());
-
-        if required_lamports > 0 {
-            msg!("Transfer {} lamports to the voter", required_lamports);
-            invoke(
-                &system_instruction::transfer(
-                    &from_info.key,
-                    &new_voter_info.key,
-                    required_lamports,
-                ),
-                &[
-                    from_info.clone(),
-                    new_voter_info.clone(),
-                    system_program_info.clone(),
-                ],
-            )?;
-        }
-
-        msg!("Allocate space for the voter");
-        invoke_signed(
-            &system_instruction::allocate(new_voter_info.key, Voter::LEN as u64),
-            &[new_voter_info.clone(), system_program_info.clone()],
-            &[&seeds],
-        )?;
-
-        msg!("Assign voter to QF Program");
-        invoke_signed(
-            &system_instruction::assign(new_voter_info.key, &program_id),
-            &[new_voter_info.clone(), system_program_info.clone()],
-            &[&seeds],
+        msg!("Create the voter");
+        Self::create_program_account(
+            program_id,
+            new_voter_info,
+            from_info,
+            system_program_info,
+            rent,
+            Voter::LEN,
+            seeds,
         )?;
 
         let mut voter = Voter::unpack_unchecked(&new_voter_info.data.borrow())?;
@@ -271,6 +589,10 @@ impl Processor {
         voter.is_initialized = true;
         voter.votes = 0;
         voter.votes_sqrt = U256::from(0);
+        voter.amount = 0;
+        voter.mint = Pubkey::default();
+        voter.weight_bps = BPS_DENOMINATOR as u16;
+        voter.payer = *from_info.key;
 
         Voter::pack(voter, &mut new_voter_info.data.borrow_mut())?;
 
@@ -292,6 +614,7 @@ impl Processor {
         let to_info = next_account_info(account_info_iter)?;
         let from_auth_info = next_account_info(account_info_iter)?;
         let token_program_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
 
         if round_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
@@ -300,29 +623,53 @@ impl Processor {
         if round.status != RoundStatus::Ongoing {
             return Err(QFError::RoundStatusError.into());
         }
-        if to_info.key != &round.vault {
-            return Err(QFError::VaultMismatch.into());
+        if clock.unix_timestamp < round.start_time {
+            return Err(QFError::RoundNotStarted.into());
+        }
+        if clock.unix_timestamp >= round.end_time {
+            return Err(QFError::RoundEnded.into());
         }
+        let (value, round_mint) = Self::unpack_contribution(
+            program_id,
+            round_info,
+            &round,
+            mint_info.key,
+            to_info,
+            account_info_iter,
+            amount,
+        )?;
 
         if project_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
-        let mut project = Project::unpack(&project_info.data.borrow())?;
+        let mut project = Project::unpack_account(&project_info.data.borrow())?;
         if project.round != *round_info.key {
             return Err(QFError::RoundMismatch.into());
         }
+        if project.status != ProjectStatus::Approved {
+            return Err(QFError::ProjectNotApproved.into());
+        }
 
+        if !from_auth_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
         if voter_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
         let (expected_key, _) = Pubkey::find_program_address(
-            &[&project_info.key.to_bytes(), &from_info.key.to_bytes()],
-            &program_id,
+            &[
+                b"voter",
+                &project_info.key.to_bytes(),
+                &from_auth_info.key.to_bytes(),
+            ],
+            program_id,
         );
         if voter_info.key != &expected_key {
             return Err(QFError::VoterMismatch.into());
         }
         let mut voter = Voter::unpack(&voter_info.data.borrow())?;
+        let weight_bps =
+            Self::vote_weight(program_id, &round, from_auth_info.key, account_info_iter)?;
 
         if token_program_info.key != &spl_token::ID {
             return Err(QFError::UnexpectedTokenProgramID.into());
@@ -330,12 +677,12 @@ impl Processor {
 
         invoke(
             &spl_token::instruction::transfer_checked(
-                &token_program_info.key,
-                &from_info.key,
-                &mint_info.key,
-                &to_info.key,
-                &from_auth_info.key,
-                &[&from_auth_info.key],
+                token_program_info.key,
+                from_info.key,
+                mint_info.key,
+                to_info.key,
+                from_auth_info.key,
+                &[from_auth_info.key],
                 amount,
                 decimals,
             )?,
@@ -347,236 +694,3077 @@ impl Processor {
                 token_program_info.clone(),
             ],
         )?;
-        round.area = round.area.checked_sub(project.area).unwrap();
-
-        let mut project_area_sqrt = PreciseNumber {
-            value: project.area_sqrt,
-        };
-
-        let new_votes_sqrt = PreciseNumber {
-            value: U256::from(voter.votes.checked_add(amount).unwrap())
-                .checked_mul(U256::from(ONE))
-                .unwrap(),
-        }
-        .sqrt()
-        .unwrap();
-
-        project_area_sqrt = project_area_sqrt
-            .checked_sub(&PreciseNumber {
-                value: voter.votes_sqrt,
-            })
-            .unwrap()
-            .checked_add(&new_votes_sqrt)
-            .unwrap();
-        project.area = project_area_sqrt.checked_pow(2).unwrap().value;
-
-        round.area = round.area.checked_add(project.area).unwrap();
+        Self::add_votes(
+            &mut round,
+            &mut project,
+            &mut voter,
+            mint_info.key,
+            value,
+            amount,
+            weight_bps,
+        )?;
+        Self::add_deposit(&mut round, round_mint, amount, false)?;
         Round::pack(round, &mut round_info.data.borrow_mut())?;
-
-        project.area_sqrt = project_area_sqrt.value;
-        project.votes = project.votes.checked_add(amount).unwrap();
-        Project::pack(project, &mut project_info.data.borrow_mut())?;
-
-        voter.votes = voter.votes.checked_add(amount).unwrap();
-        voter.votes_sqrt = new_votes_sqrt.value;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
         Voter::pack(voter, &mut voter_info.data.borrow_mut())?;
 
         Ok(())
     }
 
-    pub fn process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    pub fn process_vote_native(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let round_info = next_account_info(account_info_iter)?;
-        let vault_info = next_account_info(account_info_iter)?;
-        let vault_owner_info = next_account_info(account_info_iter)?;
         let project_info = next_account_info(account_info_iter)?;
-        let project_owner_info = next_account_info(account_info_iter)?;
+        let voter_info = next_account_info(account_info_iter)?;
+        let from_info = next_account_info(account_info_iter)?;
         let to_info = next_account_info(account_info_iter)?;
         let token_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
 
         if round_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
         let mut round = Round::unpack(&round_info.data.borrow())?;
-        if round.status != RoundStatus::Finished {
+        if round.status != RoundStatus::Ongoing {
             return Err(QFError::RoundStatusError.into());
         }
+        if clock.unix_timestamp < round.start_time {
+            return Err(QFError::RoundNotStarted.into());
+        }
+        if clock.unix_timestamp >= round.end_time {
+            return Err(QFError::RoundEnded.into());
+        }
+        let (value, round_mint) = Self::unpack_contribution(
+            program_id,
+            round_info,
+            &round,
+            &spl_token::native_mint::ID,
+            to_info,
+            account_info_iter,
+            amount,
+        )?;
 
         if project_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
-        let mut project = Project::unpack(&project_info.data.borrow())?;
+        let mut project = Project::unpack_account(&project_info.data.borrow())?;
         if project.round != *round_info.key {
             return Err(QFError::RoundMismatch.into());
         }
-        if project.withdraw {
-            return Err(QFError::ProjectAlreadyWithdraw.into());
+        if project.status != ProjectStatus::Approved {
+            return Err(QFError::ProjectNotApproved.into());
         }
-        if !project_owner_info.is_signer {
+
+        if !from_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
-        if project.owner != *project_owner_info.key {
-            return Err(QFError::OwnerMismatch.into());
+        if voter_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[
+                b"voter",
+                &project_info.key.to_bytes(),
+                &from_info.key.to_bytes(),
+            ],
+            program_id,
+        );
+        if voter_info.key != &expected_key {
+            return Err(QFError::VoterMismatch.into());
         }
+        let mut voter = Voter::unpack(&voter_info.data.borrow())?;
+        let weight_bps = Self::vote_weight(program_id, &round, from_info.key, account_info_iter)?;
 
         if token_program_info.key != &spl_token::ID {
             return Err(QFError::UnexpectedTokenProgramID.into());
         }
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
 
-        let seeds: &[&[_]] = &[
-            &round_info.key.to_bytes(),
-            &[Pubkey::find_program_address(&[&round_info.key.to_bytes()], &program_id).1],
-        ];
-
-        let fund = U256::from(round.fund);
-        let mut amount = project.votes;
-
-        amount = amount
-            .checked_add(
-                fund.checked_mul(project.area)
-                    .unwrap()
-                    .checked_div(round.area)
-                    .unwrap()
-                    .as_u64(),
-            )
-            .unwrap();
-
-        // charge 5% fee
-        let fee = amount.checked_mul(5).unwrap().checked_div(100).unwrap();
-        let amount = amount.checked_sub(fee).unwrap();
-
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                &token_program_info.key,
-                &vault_info.key,
-                &to_info.key,
-                &vault_owner_info.key,
-                &[&vault_owner_info.key],
-                amount,
-            )?,
-            &[
-                vault_info.clone(),
-                to_info.clone(),
-                vault_owner_info.clone(),
-                token_program_info.clone(),
-            ],
-            &[&seeds],
+        Self::wrap_lamports(
+            from_info,
+            to_info,
+            system_program_info,
+            token_program_info,
+            amount,
         )?;
 
-        project.withdraw = true;
-        Project::pack(project, &mut project_info.data.borrow_mut())?;
-
-        round.fee = round.fee.checked_add(fee).unwrap();
+        Self::add_votes(
+            &mut round,
+            &mut project,
+            &mut voter,
+            &spl_token::native_mint::ID,
+            value,
+            amount,
+            weight_bps,
+        )?;
+        Self::add_deposit(&mut round, round_mint, amount, false)?;
         Round::pack(round, &mut round_info.data.borrow_mut())?;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+        Voter::pack(voter, &mut voter_info.data.borrow_mut())?;
 
         Ok(())
     }
 
-    pub fn process_end_round(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let round_info = next_account_info(account_info_iter)?;
-        let owner_info = next_account_info(account_info_iter)?;
-
-        if round_info.owner != program_id {
-            return Err(ProgramError::IncorrectProgramId);
+    /// Reads the attestation account following the vote accounts of a round
+    /// gated by an identity issuer, and returns the share of the votes of
+    /// `wallet` which is matched, its trust score. Wallets without an
+    /// attestation still vote, their votes count as direct contributions only.
+    fn vote_weight(
+        program_id: &Pubkey,
+        round: &Round,
+        wallet: &Pubkey,
+        account_info_iter: &mut Iter<AccountInfo>,
+    ) -> Result<u16, ProgramError> {
+        if !round.is_identity_gated() {
+            return Ok(BPS_DENOMINATOR as u16);
         }
-        let mut round = Round::unpack(&round_info.data.borrow())?;
-        if round.status != RoundStatus::Ongoing {
-            return Err(QFError::RoundStatusError.into());
+        let attestation_info = next_account_info(account_info_iter)?;
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[
+                b"attestation",
+                &round.identity_issuer.to_bytes(),
+                &wallet.to_bytes(),
+            ],
+            program_id,
+        );
+        if attestation_info.key != &expected_key {
+            return Err(QFError::AttestationMismatch.into());
         }
-
-        if owner_info.key != &round.owner {
-            return Err(QFError::OwnerMismatch.into());
+        if attestation_info.owner != program_id || attestation_info.data_len() != Attestation::LEN {
+            return Ok(0);
         }
-        if !owner_info.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        let attestation = Attestation::unpack_unchecked(&attestation_info.data.borrow())?;
+        if !attestation.is_initialized() {
+            return Ok(0);
         }
+        Ok(attestation.trust_bps)
+    }
 
-        round.status = RoundStatus::Finished;
-        Round::pack(round, &mut round_info.data.borrow_mut())?;
+    /// Pairwise area of the votes of a single voter, its weighted square root
+    /// of the votes squared
+    fn own_area(votes_sqrt: &PreciseNumber) -> Result<U256, ProgramError> {
+        Ok(votes_sqrt
+            .checked_pow(2)
+            .ok_or(QFError::MathOverflow)?
+            .value)
+    }
 
-        Ok(())
+    /// Square root of the votes of a voter scaled by their weight
+    fn weighted_votes_sqrt(votes: u64, weight_bps: u16) -> Result<PreciseNumber, ProgramError> {
+        Ok(PreciseNumber {
+            value: U256::from(votes)
+                .checked_mul(U256::from(ONE))
+                .ok_or(QFError::MathOverflow)?,
+        }
+        .sqrt()
+        .ok_or(QFError::MathOverflow)?
+        .checked_mul(&PreciseNumber::new(weight_bps as u128).ok_or(QFError::MathOverflow)?)
+        .ok_or(QFError::MathOverflow)?
+        .checked_div(&PreciseNumber::new(BPS_DENOMINATOR as u128).ok_or(QFError::MathOverflow)?)
+        .ok_or(QFError::MathOverflow)?)
     }
 
-    pub fn process_withdraw_fee(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let round_info = next_account_info(account_info_iter)?;
-        let owner_info = next_account_info(account_info_iter)?;
-        let vault_info = next_account_info(account_info_iter)?;
-        let vault_owner_info = next_account_info(account_info_iter)?;
-        let to_info = next_account_info(account_info_iter)?;
-        let token_program_info = next_account_info(account_info_iter)?;
+    /// Adds `value` of votes, paid with `amount` tokens of `mint`, of a voter to
+    /// its project and the round. The new votes are matched by `weight_bps`,
+    /// the votes the voter cast before keep the weight they were cast with.
+    #[allow(clippy::too_many_arguments)]
+    fn add_votes(
+        round: &mut Round,
+        project: &mut Project,
+        voter: &mut Voter,
+        mint: &Pubkey,
+        value: u64,
+        amount: u64,
+        weight_bps: u16,
+    ) -> ProgramResult {
+        if voter.amount == 0 {
+            voter.mint = *mint;
+        } else if voter.mint != *mint {
+            return Err(QFError::MintMismatch.into());
+        }
+
+        round.area = round
+            .area
+            .checked_sub(project.area)
+            .ok_or(QFError::MathOverflow)?;
+
+        let mut project_area_sqrt = PreciseNumber {
+            value: project.area_sqrt,
+        };
+
+        let new_votes = voter
+            .votes
+            .checked_add(value)
+            .ok_or(QFError::MathOverflow)?;
+        // only the growth of the square root by the new votes takes the new weight
+        let votes_sqrt = PreciseNumber {
+            value: voter.votes_sqrt,
+        };
+        let new_votes_sqrt = votes_sqrt
+            .checked_add(
+                &Self::weighted_votes_sqrt(new_votes, weight_bps)?
+                    .checked_sub(&Self::weighted_votes_sqrt(voter.votes, weight_bps)?)
+                    .ok_or(QFError::MathOverflow)?,
+            )
+            .ok_or(QFError::MathOverflow)?;
+
+        project_area_sqrt = project_area_sqrt
+            .checked_sub(&PreciseNumber {
+                value: voter.votes_sqrt,
+            })
+            .ok_or(QFError::MathOverflow)?
+            .checked_add(&new_votes_sqrt)
+            .ok_or(QFError::MathOverflow)?;
+        project.area = project_area_sqrt
+            .checked_pow(2)
+            .ok_or(QFError::MathOverflow)?
+            .value;
+
+        round.area = round
+            .area
+            .checked_add(project.area)
+            .ok_or(QFError::MathOverflow)?;
+
+        if voter.votes == 0 && value > 0 {
+            // every voter already on the project forms a new pair with this one
+            round.pair_count = round
+                .pair_count
+                .checked_add(project.voter_count)
+                .ok_or(QFError::MathOverflow)?;
+            project.voter_count = project
+                .voter_count
+                .checked_add(1)
+                .ok_or(QFError::MathOverflow)?;
+        }
+        if round.matching_algorithm == MatchingAlgorithm::Pairwise {
+            let old_area = Self::own_area(&votes_sqrt)?;
+            let new_area = Self::own_area(&new_votes_sqrt)?;
+            round.pairwise_area = round
+                .pairwise_area
+                .checked_sub(old_area)
+                .ok_or(QFError::MathOverflow)?
+                .checked_add(new_area)
+                .ok_or(QFError::MathOverflow)?;
+            project.pairwise_area = project
+                .pairwise_area
+                .checked_sub(old_area)
+                .ok_or(QFError::MathOverflow)?
+                .checked_add(new_area)
+                .ok_or(QFError::MathOverflow)?;
+        }
+        round.votes = round
+            .votes
+            .checked_add(value)
+            .ok_or(QFError::MathOverflow)?;
+
+        project.area_sqrt = project_area_sqrt.value;
+        project.votes = project
+            .votes
+            .checked_add(value)
+            .ok_or(QFError::MathOverflow)?;
+
+        voter.votes = new_votes;
+        voter.votes_sqrt = new_votes_sqrt.value;
+        voter.amount = voter
+            .amount
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        voter.weight_bps = weight_bps;
+        Ok(())
+    }
+
+    pub fn process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let project_owner_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        Self::pay_project(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            project_info,
+            Some(project_owner_info),
+            to_info,
+            token_program_info,
+        )
+    }
+
+    pub fn process_distribute_to_project(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        // the share in the vault of another mint follows its round mint account
+        match account_info_iter.next() {
+            None => Self::pay_project(
+                program_id,
+                round_info,
+                vault_info,
+                vault_owner_info,
+                project_info,
+                None,
+                to_info,
+                token_program_info,
+            ),
+            Some(round_mint_info) => {
+                let receipt_info = next_account_info(account_info_iter)?;
+                let payer_info = next_account_info(account_info_iter)?;
+                let system_program_info = next_account_info(account_info_iter)?;
+                let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+                Self::pay_project_mint(
+                    program_id,
+                    round_info,
+                    project_info,
+                    None,
+                    round_mint_info,
+                    vault_info,
+                    vault_owner_info,
+                    to_info,
+                    receipt_info,
+                    payer_info,
+                    token_program_info,
+                    system_program_info,
+                    rent,
+                    account_info_iter.next(),
+                )
+            }
+        }
+    }
+
+    /// Pays out a project of a finished round to `to_info`. Without the
+    /// project owner signing, `to_info` has to be the payout account in the
+    /// metadata of the project unless the project is owed nothing.
+    #[allow(clippy::too_many_arguments)]
+    fn pay_project<'a>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo<'a>,
+        vault_info: &AccountInfo<'a>,
+        vault_owner_info: &AccountInfo<'a>,
+        project_info: &AccountInfo<'a>,
+        project_owner_info: Option<&AccountInfo<'a>>,
+        to_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if vault_info.key != &round.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if project.withdraw {
+            return Err(QFError::ProjectAlreadyWithdraw.into());
+        }
+        let metadata = ProjectMetadata::unpack(&project_info.data.borrow()[Project::LEN..])?;
+        let Payout { amount, fee } = Self::project_payout(&round, &project)?;
+        match project_owner_info {
+            Some(project_owner_info) => {
+                if !project_owner_info.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if project.owner != *project_owner_info.key {
+                    return Err(QFError::OwnerMismatch.into());
+                }
+            }
+            // a project owed nothing, like a rejected one, is settled by
+            // anyone so it does not keep the round from closing
+            None => {
+                if metadata.payout == Pubkey::default() && (amount != 0 || fee != 0) {
+                    return Err(QFError::PayoutNotSet.into());
+                }
+            }
+        }
+        if metadata.payout != Pubkey::default() && metadata.payout != *to_info.key {
+            return Err(QFError::PayoutMismatch.into());
+        }
+
+        Self::check_token_account_mint(to_info, &round.mint)?;
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        // matching funds of a vesting round stay in the escrow of the project
+        let escrowed = payout::escrowed_payout(&round, &project, &Payout { amount, fee })?;
+        let amount = amount.checked_sub(escrowed).ok_or(QFError::MathOverflow)?;
+
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            payout::vault_share(&round, amount, round.deposited, round.donated)?,
+        )?;
+
+        project.withdraw = true;
+        project.escrowed = escrowed;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+
+        round.escrowed = round
+            .escrowed
+            .checked_add(escrowed)
+            .ok_or(QFError::MathOverflow)?;
+
+        round.withdrawn_count = round
+            .withdrawn_count
+            .checked_add(1)
+            .ok_or(QFError::MathOverflow)?;
+        round.paid_out = round
+            .paid_out
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        round.fee = round.fee.checked_add(fee).ok_or(QFError::MathOverflow)?;
+        round.fee_total = round
+            .fee_total
+            .checked_add(fee)
+            .ok_or(QFError::MathOverflow)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_end_round(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Ongoing {
+            return Err(QFError::RoundStatusError.into());
+        }
+
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        round.status = RoundStatus::Finished;
+        round.finished_time = clock.unix_timestamp;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_finalize_round(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Ongoing {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if clock.unix_timestamp < round.end_time {
+            return Err(QFError::RoundNotEnded.into());
+        }
+
+        round.status = RoundStatus::Finished;
+        round.finished_time = clock.unix_timestamp;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_settle_matching(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        // projects never sign, the signers of a multisig owner follow them
+        let remaining_infos = account_info_iter.as_slice();
+        let (project_infos, signer_infos) = remaining_infos.split_at(
+            remaining_infos
+                .iter()
+                .position(|info| info.is_signer)
+                .unwrap_or(remaining_infos.len()),
+        );
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+        Self::check_authority(&round.owner, owner_info, signer_infos)?;
+        if round.matching_cap_bps == 0 {
+            return Err(QFError::InvalidMatchingCap.into());
+        }
+        if round.matching_settled {
+            return Err(QFError::MatchingAlreadySettled.into());
+        }
+        if round.matching_algorithm == MatchingAlgorithm::Pairwise
+            && round.pairs_distributed != round.pair_count
+        {
+            return Err(QFError::MatchingNotSettled.into());
+        }
+
+        if project_infos.is_empty() {
+            return Err(QFError::IncompleteProjects.into());
+        }
+        for project_info in project_infos {
+            if project_info.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let mut project = Project::unpack_account(&project_info.data.borrow())?;
+            if project.round != *round_info.key {
+                return Err(QFError::RoundMismatch.into());
+            }
+            project.matched = payout::settle_next(&mut round, &project)?;
+            Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+        }
+
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_init_voter_pair(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let new_voter_pair_info = next_account_info(account_info_iter)?;
+        let round_info = next_account_info(account_info_iter)?;
+        let voter_a_wallet_info = next_account_info(account_info_iter)?;
+        let voter_b_wallet_info = next_account_info(account_info_iter)?;
+        let from_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if round.matching_algorithm != MatchingAlgorithm::Pairwise {
+            return Err(QFError::MatchingAlgorithmMismatch.into());
+        }
+
+        // a pair is stored once, ordered by the voter wallet keys
+        if voter_a_wallet_info.key >= voter_b_wallet_info.key {
+            return Err(QFError::VoterPairMismatch.into());
+        }
+
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+
+        let (_, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"voter_pair",
+                &round_info.key.to_bytes(),
+                &voter_a_wallet_info.key.to_bytes(),
+                &voter_b_wallet_info.key.to_bytes(),
+            ],
+            program_id,
+        );
+        let seeds: &[&[_]] = &[
+            b"voter_pair",
+            &round_info.key.to_bytes(),
+            &voter_a_wallet_info.key.to_bytes(),
+            &voter_b_wallet_info.key.to_bytes(),
+            &[bump_seed],
+        ];
+
+        msg!("Create the voter pair");
+        Self::create_program_account(
+            program_id,
+            new_voter_pair_info,
+            from_info,
+            system_program_info,
+            rent,
+            VoterPair::account_len(round.project_count),
+            seeds,
+        )?;
+
+        let mut voter_pair =
+            VoterPair::unpack_unchecked(&new_voter_pair_info.data.borrow()[..VoterPair::LEN])?;
+        if voter_pair.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        voter_pair.is_initialized = true;
+        voter_pair.round = *round_info.key;
+        voter_pair.voter_a = *voter_a_wallet_info.key;
+        voter_pair.voter_b = *voter_b_wallet_info.key;
+        voter_pair.overlap = U256::zero();
+
+        VoterPair::pack(
+            voter_pair,
+            &mut new_voter_pair_info.data.borrow_mut()[..VoterPair::LEN],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_accumulate_voter_pair(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let voter_pair_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let voter_a_info = next_account_info(account_info_iter)?;
+        let voter_b_info = next_account_info(account_info_iter)?;
+
+        let (mut round, mut voter_pair, project, overlap) = Self::unpack_voter_pair_accounts(
+            program_id,
+            round_info,
+            voter_pair_info,
+            project_info,
+            voter_a_info,
+            voter_b_info,
+        )?;
+
+        let mut voter_pair_data = voter_pair_info.data.borrow_mut();
+        let offset = VoterPair::LEN + (project.index / 8) as usize;
+        let mask = 1u8 << (project.index % 8);
+        if voter_pair_data[offset] & mask != 0 {
+            return Err(QFError::PairAlreadyProcessed.into());
+        }
+        voter_pair_data[offset] |= mask;
+
+        voter_pair.overlap = voter_pair
+            .overlap
+            .checked_add(overlap)
+            .ok_or(QFError::MathOverflow)?;
+        VoterPair::pack(voter_pair, &mut voter_pair_data[..VoterPair::LEN])?;
+
+        round.pairs_accumulated = round
+            .pairs_accumulated
+            .checked_add(1)
+            .ok_or(QFError::MathOverflow)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_distribute_voter_pair(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let voter_pair_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let voter_a_info = next_account_info(account_info_iter)?;
+        let voter_b_info = next_account_info(account_info_iter)?;
+
+        let (mut round, voter_pair, mut project, overlap) = Self::unpack_voter_pair_accounts(
+            program_id,
+            round_info,
+            voter_pair_info,
+            project_info,
+            voter_a_info,
+            voter_b_info,
+        )?;
+        if round.pairs_accumulated != round.pair_count {
+            return Err(QFError::PairwiseNotAccumulated.into());
+        }
+
+        let mut voter_pair_data = voter_pair_info.data.borrow_mut();
+        let accumulated_offset = VoterPair::LEN + (project.index / 8) as usize;
+        let distributed_offset = accumulated_offset + VoterPair::bitmap_len(round.project_count);
+        let mask = 1u8 << (project.index % 8);
+        if voter_pair_data[accumulated_offset] & mask == 0 {
+            return Err(QFError::PairwiseNotAccumulated.into());
+        }
+        if voter_pair_data[distributed_offset] & mask != 0 {
+            return Err(QFError::PairAlreadyProcessed.into());
+        }
+        voter_pair_data[distributed_offset] |= mask;
+
+        // both cross terms of the pair, discounted by threshold / (threshold + overlap)
+        let threshold = U256::from(round.pairwise_threshold)
+            .checked_mul(U256::from(ONE))
+            .ok_or(QFError::MathOverflow)?;
+        let area = overlap
+            .checked_mul(U256::from(2))
+            .ok_or(QFError::MathOverflow)?
+            .checked_mul(threshold)
+            .ok_or(QFError::MathOverflow)?
+            .checked_div(
+                threshold
+                    .checked_add(voter_pair.overlap)
+                    .ok_or(QFError::MathOverflow)?,
+            )
+            .ok_or(QFError::MathOverflow)?;
+
+        project.pairwise_area = project
+            .pairwise_area
+            .checked_add(area)
+            .ok_or(QFError::MathOverflow)?;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+
+        round.pairwise_area = round
+            .pairwise_area
+            .checked_add(area)
+            .ok_or(QFError::MathOverflow)?;
+        round.pairs_distributed = round
+            .pairs_distributed
+            .checked_add(1)
+            .ok_or(QFError::MathOverflow)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Checks the accounts shared by the voter pair cranks and returns the
+    /// overlap of the pair on the project, sqrt(votes_a * votes_b).
+    fn unpack_voter_pair_accounts<'a>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo<'a>,
+        voter_pair_info: &AccountInfo<'a>,
+        project_info: &AccountInfo<'a>,
+        voter_a_info: &AccountInfo<'a>,
+        voter_b_info: &AccountInfo<'a>,
+    ) -> Result<(Round, VoterPair, Project, U256), ProgramError> {
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if round.matching_algorithm != MatchingAlgorithm::Pairwise {
+            return Err(QFError::MatchingAlgorithmMismatch.into());
+        }
+
+        if voter_pair_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if voter_pair_info.data_len() != VoterPair::account_len(round.project_count) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let voter_pair = VoterPair::unpack(&voter_pair_info.data.borrow()[..VoterPair::LEN])?;
+        if voter_pair.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+
+        let mut votes_sqrt = Vec::with_capacity(2);
+        for (voter_info, wallet) in [
+            (voter_a_info, &voter_pair.voter_a),
+            (voter_b_info, &voter_pair.voter_b),
+        ] {
+            if voter_info.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let (expected_key, _) = Pubkey::find_program_address(
+                &[b"voter", &project_info.key.to_bytes(), &wallet.to_bytes()],
+                program_id,
+            );
+            if voter_info.key != &expected_key {
+                return Err(QFError::VoterMismatch.into());
+            }
+            let voter = Voter::unpack(&voter_info.data.borrow())?;
+            if voter.votes == 0 {
+                return Err(QFError::PairNotOverlapped.into());
+            }
+            votes_sqrt.push(PreciseNumber {
+                value: voter.votes_sqrt,
+            });
+        }
+        let overlap = votes_sqrt[0]
+            .checked_mul(&votes_sqrt[1])
+            .ok_or(QFError::MathOverflow)?
+            .value;
+
+        Ok((round, voter_pair, project, overlap))
+    }
+
+    pub fn process_cancel_round(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Ongoing && round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+        // a withdrawal of a vesting round can pay nothing but escrow the matching
+        if round.paid_out != 0
+            || round.fee != 0
+            || round.escrowed != 0
+            || round.withdrawn_count != 0
+        {
+            return Err(QFError::RoundAlreadyPaidOut.into());
+        }
+
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        round.status = RoundStatus::Cancelled;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_refund_donation(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let donor_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        match round.status {
+            RoundStatus::Cancelled => {}
+            // donors of a finished round only get their donations back when
+            // nothing of the matching pool is matched
+            RoundStatus::Finished
+                if payout::empty_round_policy(&round) == EmptyRoundPolicy::RefundDonors =>
+            {
+                if !payout::is_empty_round(&round) {
+                    return Err(QFError::RoundNotEmpty.into());
+                }
+            }
+            _ => return Err(QFError::RoundStatusError.into()),
+        }
+
+        if donor_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut donor = Donor::unpack(&donor_info.data.borrow())?;
+        if donor.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+
+        let round_mint = if donor.mint == round.mint {
+            if vault_info.key != &round.vault {
+                return Err(QFError::VaultMismatch.into());
+            }
+            None
+        } else {
+            let round_mint_info = next_account_info(account_info_iter)?;
+            let round_mint = Self::unpack_round_mint(
+                program_id,
+                round_info,
+                round_mint_info,
+                &donor.mint,
+                vault_info.key,
+            )?;
+            Some((round_mint_info, round_mint))
+        };
+
+        // refunds only go back to a token account of the donor
+        if to_info.owner != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+        let to = spl_token::state::Account::unpack(&to_info.data.borrow())?;
+        if to.owner != donor.authority {
+            return Err(QFError::DonorMismatch.into());
+        }
+        if to.mint != donor.mint {
+            return Err(QFError::MintMismatch.into());
+        }
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            donor.amount,
+        )?;
+
+        round.fund = round
+            .fund
+            .checked_sub(donor.value)
+            .ok_or(QFError::MathOverflow)?;
+        Self::remove_deposit(&mut round, round_mint, donor.amount, true)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        donor.amount = 0;
+        donor.value = 0;
+        Donor::pack(donor, &mut donor_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_refund_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let voter_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Cancelled {
+            return Err(QFError::RoundStatusError.into());
+        }
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+
+        if to_info.owner != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+        let to = spl_token::state::Account::unpack(&to_info.data.borrow())?;
+        let mint = to.mint;
+
+        // votes only go back to a token account of the voter wallet
+        if voter_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[b"voter", &project_info.key.to_bytes(), &to.owner.to_bytes()],
+            program_id,
+        );
+        if voter_info.key != &expected_key {
+            return Err(QFError::VoterMismatch.into());
+        }
+        let mut voter = Voter::unpack(&voter_info.data.borrow())?;
+        if voter.amount > 0 && voter.mint != mint {
+            return Err(QFError::MintMismatch.into());
+        }
+
+        // the vault of the mint the votes were paid in
+        let round_mint = if mint == round.mint {
+            if vault_info.key != &round.vault {
+                return Err(QFError::VaultMismatch.into());
+            }
+            None
+        } else {
+            let round_mint_info = next_account_info(account_info_iter)?;
+            let round_mint = Self::unpack_round_mint(
+                program_id,
+                round_info,
+                round_mint_info,
+                &mint,
+                vault_info.key,
+            )?;
+            Some((round_mint_info, round_mint))
+        };
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            voter.amount,
+        )?;
+
+        project.votes = project
+            .votes
+            .checked_sub(voter.votes)
+            .ok_or(QFError::MathOverflow)?;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+
+        round.votes = round
+            .votes
+            .checked_sub(voter.votes)
+            .ok_or(QFError::MathOverflow)?;
+        Self::remove_deposit(&mut round, round_mint, voter.amount, false)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        voter.votes = 0;
+        voter.votes_sqrt = U256::zero();
+        voter.amount = 0;
+        Voter::pack(voter, &mut voter_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Checks that `authority_info` is the expected authority and has signed.
+    /// An authority which is an SPL token multisig account signs with at least
+    /// `m` of its signers among `signer_infos`.
+    fn check_authority(
+        expected_authority: &Pubkey,
+        authority_info: &AccountInfo,
+        signer_infos: &[AccountInfo],
+    ) -> ProgramResult {
+        if authority_info.key != expected_authority {
+            return Err(QFError::OwnerMismatch.into());
+        }
+        if authority_info.owner == &spl_token::ID
+            && authority_info.data_len() == spl_token::state::Multisig::LEN
+        {
+            let multisig = spl_token::state::Multisig::unpack(&authority_info.data.borrow())?;
+            let signers = &multisig.signers[..multisig.n as usize];
+            let mut matched = [false; spl_token::instruction::MAX_SIGNERS];
+            let mut num_signers = 0u8;
+            for signer_info in signer_infos.iter().filter(|info| info.is_signer) {
+                for (position, key) in signers.iter().enumerate() {
+                    if key == signer_info.key && !matched[position] {
+                        matched[position] = true;
+                        num_signers += 1;
+                    }
+                }
+            }
+            if num_signers < multisig.m {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            return Ok(());
+        }
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(())
+    }
+
+    /// Checks that the round of a project which has not withdrawn is
+    /// cancelled. A closed round counts as cancelled, it only closes before
+    /// every project has withdrawn once all votes are refunded.
+    fn check_cancelled_round(
+        program_id: &Pubkey,
+        round_info: &AccountInfo,
+        project: &Project,
+    ) -> ProgramResult {
+        if round_info.key != &project.round {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if round_info.owner == program_id {
+            let round = Round::unpack(&round_info.data.borrow())?;
+            if round.status != RoundStatus::Cancelled {
+                return Err(QFError::ProjectNotWithdrawn.into());
+            }
+        } else if !round_info.data_is_empty() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(())
+    }
+
+    /// Checks that a project which has withdrawn from the round vault has also
+    /// withdrawn from the vault of every other mint, each round mint follows
+    /// with the withdraw receipt of the project. The round only closes once
+    /// every project has, so a closed round needs no receipts.
+    fn check_mint_receipts<'a, 'b: 'a>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo,
+        project_info: &AccountInfo,
+        project: &Project,
+        account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    ) -> ProgramResult {
+        if round_info.key != &project.round {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if round_info.owner != program_id {
+            if !round_info.data_is_empty() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            return Ok(());
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+
+        let mut mints = Vec::with_capacity(round.mint_count as usize);
+        for _ in 0..round.mint_count {
+            let round_mint_info = next_account_info(account_info_iter)?;
+            let receipt_info = next_account_info(account_info_iter)?;
+
+            if round_mint_info.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let round_mint = RoundMint::unpack(&round_mint_info.data.borrow())?;
+            if round_mint.round != *round_info.key {
+                return Err(QFError::RoundMismatch.into());
+            }
+            // a round mint given twice would leave another one unchecked
+            if mints.contains(&round_mint.mint) {
+                return Err(QFError::ProjectNotWithdrawn.into());
+            }
+            mints.push(round_mint.mint);
+
+            let (expected_receipt, _) = Pubkey::find_program_address(
+                &[
+                    b"receipt",
+                    &project_info.key.to_bytes(),
+                    &round_mint.mint.to_bytes(),
+                ],
+                program_id,
+            );
+            if receipt_info.key != &expected_receipt {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if receipt_info.owner != program_id {
+                return Err(QFError::ProjectNotWithdrawn.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the payout account in the metadata of a project, which
+    /// follows the accounts of the instruction when it is set, is a token
+    /// account of the round mint. A project could not withdraw otherwise.
+    fn check_payout_account<'a, 'b: 'a>(
+        metadata: &ProjectMetadata,
+        round: &Round,
+        account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    ) -> ProgramResult {
+        if metadata.payout == Pubkey::default() {
+            return Ok(());
+        }
+        let payout_info = next_account_info(account_info_iter)?;
+        if payout_info.key != &metadata.payout {
+            return Err(QFError::PayoutMismatch.into());
+        }
+        Self::check_token_account_mint(payout_info, &round.mint)
+    }
+
+    /// Checks that a token account holds the mint of the round.
+    fn check_token_account_mint(token_account_info: &AccountInfo, mint: &Pubkey) -> ProgramResult {
+        if token_account_info.owner != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+        let token_account = spl_token::state::Account::unpack(&token_account_info.data.borrow())?;
+        if token_account.mint != *mint {
+            return Err(QFError::MintMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Checks the vault a contribution of `amount` in `mint` is paid to, and
+    /// returns its value in the round mint with the round mint account when
+    /// `mint` is another mint accepted by the round.
+    fn unpack_contribution<'a, 'b>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo<'b>,
+        round: &Round,
+        mint: &Pubkey,
+        vault_info: &AccountInfo<'b>,
+        account_info_iter: &mut Iter<'a, AccountInfo<'b>>,
+        amount: u64,
+    ) -> Result<Contribution<'a, 'b>, ProgramError> {
+        if mint == &round.mint {
+            if vault_info.key != &round.vault {
+                return Err(QFError::VaultMismatch.into());
+            }
+            return Ok((amount, None));
+        }
+
+        let round_mint_info = next_account_info(account_info_iter)?;
+        let price_feed_info = next_account_info(account_info_iter)?;
+        let round_mint = Self::unpack_round_mint(
+            program_id,
+            round_info,
+            round_mint_info,
+            mint,
+            vault_info.key,
+        )?;
+        let price = Self::unpack_price(program_id, &round_mint, price_feed_info)?;
+        Ok((
+            payout::convert(amount, price)?,
+            Some((round_mint_info, round_mint)),
+        ))
+    }
+
+    /// Wraps `amount` lamports of `from` into a wrapped SOL token account.
+    fn wrap_lamports<'a>(
+        from_info: &AccountInfo<'a>,
+        to_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        invoke(
+            &system_instruction::transfer(from_info.key, to_info.key, amount),
+            &[
+                from_info.clone(),
+                to_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::sync_native(token_program_info.key, to_info.key)?,
+            &[to_info.clone(), token_program_info.clone()],
+        )
+    }
+
+    /// Checks a round mint account against the round, its mint and its vault.
+    fn unpack_round_mint(
+        program_id: &Pubkey,
+        round_info: &AccountInfo,
+        round_mint_info: &AccountInfo,
+        mint: &Pubkey,
+        vault: &Pubkey,
+    ) -> Result<RoundMint, ProgramError> {
+        if round_mint_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round_mint = RoundMint::unpack(&round_mint_info.data.borrow())?;
+        if round_mint.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if round_mint.mint != *mint {
+            return Err(QFError::MintMismatch.into());
+        }
+        if round_mint.vault != *vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+        Ok(round_mint)
+    }
+
+    /// Reads the price of a round mint from its price feed.
+    fn unpack_price(
+        program_id: &Pubkey,
+        round_mint: &RoundMint,
+        price_feed_info: &AccountInfo,
+    ) -> Result<u64, ProgramError> {
+        if price_feed_info.key != &round_mint.price_feed {
+            return Err(QFError::PriceFeedMismatch.into());
+        }
+        if price_feed_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(PriceFeed::unpack(&price_feed_info.data.borrow())?.price)
+    }
+
+    /// Records `amount` deposited in the vault of the round or of a round mint,
+    /// `donation` tells donations apart from votes.
+    fn add_deposit(
+        round: &mut Round,
+        round_mint: Option<(&AccountInfo, RoundMint)>,
+        amount: u64,
+        donation: bool,
+    ) -> ProgramResult {
+        let donated = if donation { amount } else { 0 };
+        match round_mint {
+            None => {
+                round.deposited = round
+                    .deposited
+                    .checked_add(amount)
+                    .ok_or(QFError::MathOverflow)?;
+                round.donated = round
+                    .donated
+                    .checked_add(donated)
+                    .ok_or(QFError::MathOverflow)?;
+            }
+            Some((round_mint_info, mut round_mint)) => {
+                round_mint.deposited = round_mint
+                    .deposited
+                    .checked_add(amount)
+                    .ok_or(QFError::MathOverflow)?;
+                round_mint.donated = round_mint
+                    .donated
+                    .checked_add(donated)
+                    .ok_or(QFError::MathOverflow)?;
+                RoundMint::pack(round_mint, &mut round_mint_info.data.borrow_mut())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records `amount` refunded from the vault of the round or of a round mint.
+    fn remove_deposit(
+        round: &mut Round,
+        round_mint: Option<(&AccountInfo, RoundMint)>,
+        amount: u64,
+        donation: bool,
+    ) -> ProgramResult {
+        let donated = if donation { amount } else { 0 };
+        match round_mint {
+            None => {
+                round.deposited = round
+                    .deposited
+                    .checked_sub(amount)
+                    .ok_or(QFError::MathOverflow)?;
+                round.donated = round
+                    .donated
+                    .checked_sub(donated)
+                    .ok_or(QFError::MathOverflow)?;
+            }
+            Some((round_mint_info, mut round_mint)) => {
+                round_mint.deposited = round_mint
+                    .deposited
+                    .checked_sub(amount)
+                    .ok_or(QFError::MathOverflow)?;
+                round_mint.donated = round_mint
+                    .donated
+                    .checked_sub(donated)
+                    .ok_or(QFError::MathOverflow)?;
+                RoundMint::pack(round_mint, &mut round_mint_info.data.borrow_mut())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Payout of a project of a finished round, in units of the round mint.
+    fn project_payout(round: &Round, project: &Project) -> Result<Payout, ProgramError> {
+        if round.matching_algorithm == MatchingAlgorithm::Pairwise
+            && round.pairs_distributed != round.pair_count
+        {
+            return Err(QFError::MatchingNotSettled.into());
+        }
+
+        let matched = if round.matching_cap_bps == 0 {
+            payout::uncapped_matching(round, project)?
+        } else {
+            if !round.matching_settled {
+                return Err(QFError::MatchingNotSettled.into());
+            }
+            project.matched
+        };
+        Ok(payout::project_payout(round, project, matched)?)
+    }
+
+    /// Transfers `amount` out of the round vault, signed by the vault owner.
+    fn transfer_from_vault<'a>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo<'a>,
+        vault_info: &AccountInfo<'a>,
+        vault_owner_info: &AccountInfo<'a>,
+        to_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let seeds: &[&[_]] = &[
+            &round_info.key.to_bytes(),
+            &[Pubkey::find_program_address(&[&round_info.key.to_bytes()], program_id).1],
+        ];
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program_info.key,
+                vault_info.key,
+                to_info.key,
+                vault_owner_info.key,
+                &[vault_owner_info.key],
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                to_info.clone(),
+                vault_owner_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[seeds],
+        )
+    }
+
+    /// Sweeps whatever the floored payouts left behind in a vault of a round
+    /// to `to_info` and closes the vault.
+    fn sweep_vault<'a>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo<'a>,
+        vault_info: &AccountInfo<'a>,
+        vault_owner_info: &AccountInfo<'a>,
+        to_info: &AccountInfo<'a>,
+        recipient_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let residual = spl_token::state::Account::unpack(&vault_info.data.borrow())?.amount;
+        msg!("Sweep {} from the vault", residual);
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            residual,
+        )?;
+
+        msg!("Close the vault");
+        let bump_seed = Pubkey::find_program_address(&[&round_info.key.to_bytes()], program_id).1;
+        invoke_signed(
+            &spl_token::instruction::close_account(
+                token_program_info.key,
+                vault_info.key,
+                recipient_info.key,
+                vault_owner_info.key,
+                &[],
+            )?,
+            &[
+                vault_info.clone(),
+                recipient_info.clone(),
+                vault_owner_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[&round_info.key.to_bytes(), &[bump_seed]]],
+        )
+    }
+
+    /// Funds, allocates and assigns a program derived account to the program.
+    fn create_program_account<'a>(
+        program_id: &Pubkey,
+        new_account_info: &AccountInfo<'a>,
+        from_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        rent: &Rent,
+        space: usize,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let required_lamports = rent
+            .minimum_balance(space)
+            .max(1)
+            .saturating_sub(new_account_info.lamports());
+
+        if required_lamports > 0 {
+            msg!("Transfer {} lamports", required_lamports);
+            invoke(
+                &system_instruction::transfer(
+                    from_info.key,
+                    new_account_info.key,
+                    required_lamports,
+                ),
+                &[
+                    from_info.clone(),
+                    new_account_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+
+        msg!("Allocate space");
+        invoke_signed(
+            &system_instruction::allocate(new_account_info.key, space as u64),
+            &[new_account_info.clone(), system_program_info.clone()],
+            &[seeds],
+        )?;
+
+        msg!("Assign to QF Program");
+        invoke_signed(
+            &system_instruction::assign(new_account_info.key, program_id),
+            &[new_account_info.clone(), system_program_info.clone()],
+            &[seeds],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_withdraw_fee(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if round.fee == 0 {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        if vault_info.key != &round.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+
+        if to_info.key != &round.fee_recipient {
+            return Err(QFError::FeeRecipientMismatch.into());
+        }
+        Self::check_token_account_mint(to_info, &round.mint)?;
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            payout::vault_share(&round, round.fee, round.deposited, round.donated)?,
+        )?;
+
+        round.fee = 0;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_init_price_feed(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let price_feed_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if price_feed_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if price_feed_info.data_len() != PriceFeed::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if !rent.is_exempt(price_feed_info.lamports(), PriceFeed::LEN) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+        let mut price_feed = PriceFeed::unpack_unchecked(&price_feed_info.data.borrow())?;
+        if price_feed.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        if price == 0 {
+            return Err(QFError::InvalidPrice.into());
+        }
+
+        price_feed.is_initialized = true;
+        price_feed.authority = *authority_info.key;
+        price_feed.price = price;
+        PriceFeed::pack(price_feed, &mut price_feed_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_set_price(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let price_feed_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        if price_feed_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut price_feed = PriceFeed::unpack(&price_feed_info.data.borrow())?;
+        if price_feed.authority != *authority_info.key {
+            return Err(QFError::OwnerMismatch.into());
+        }
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if price == 0 {
+            return Err(QFError::InvalidPrice.into());
+        }
+
+        price_feed.price = price;
+        PriceFeed::pack(price_feed, &mut price_feed_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_add_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let round_mint_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let price_feed_info = next_account_info(account_info_iter)?;
+        let fee_recipient_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let associated_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Ongoing {
+            return Err(QFError::RoundStatusError.into());
+        }
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        if mint_info.key == &round.mint {
+            return Err(QFError::MintAlreadyAccepted.into());
+        }
+        // escrows and a pool rolled over are held in the vault of the round
+        // mint only
+        if round.is_vesting() || round.empty_round_policy == EmptyRoundPolicy::RollOver {
+            return Err(QFError::MintNotAllowed.into());
+        }
+
+        let (expected_round_mint, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"round_mint",
+                &round_info.key.to_bytes(),
+                &mint_info.key.to_bytes(),
+            ],
+            program_id,
+        );
+        if round_mint_info.key != &expected_round_mint {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !round_mint_info.data_is_empty() {
+            return Err(QFError::MintAlreadyAccepted.into());
+        }
+
+        let (expected_vault_owner, _) =
+            Pubkey::find_program_address(&[&round_info.key.to_bytes()], program_id);
+        if vault_owner_info.key != &expected_vault_owner {
+            return Err(QFError::OwnerMismatch.into());
+        }
+
+        if price_feed_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        PriceFeed::unpack(&price_feed_info.data.borrow())?;
+
+        Self::check_token_account_mint(fee_recipient_info, mint_info.key)?;
+
+        if associated_program_info.key != &spl_associated_token_account::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account(
+                payer_info.key,
+                vault_owner_info.key,
+                mint_info.key,
+                token_program_info.key,
+            ),
+            &[
+                associated_program_info.clone(),
+                payer_info.clone(),
+                vault_info.clone(),
+                vault_owner_info.clone(),
+                mint_info.clone(),
+                system_program_info.clone(),
+                token_program_info.clone(),
+                rent_sysvar_info.clone(),
+            ],
+        )?;
+
+        msg!("Create the round mint");
+        Self::create_program_account(
+            program_id,
+            round_mint_info,
+            payer_info,
+            system_program_info,
+            rent,
+            RoundMint::LEN,
+            &[
+                b"round_mint",
+                &round_info.key.to_bytes(),
+                &mint_info.key.to_bytes(),
+                &[bump_seed],
+            ],
+        )?;
+
+        let round_mint = RoundMint {
+            is_initialized: true,
+            round: *round_info.key,
+            mint: *mint_info.key,
+            vault: *vault_info.key,
+            price_feed: *price_feed_info.key,
+            fee_recipient: *fee_recipient_info.key,
+            deposited: 0,
+            fee_withdrawn: 0,
+            donated: 0,
+            withdrawn_count: 0,
+        };
+        RoundMint::pack(round_mint, &mut round_mint_info.data.borrow_mut())?;
+
+        round.mint_count = round
+            .mint_count
+            .checked_add(1)
+            .ok_or(QFError::MathOverflow)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_withdraw_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let project_owner_info = next_account_info(account_info_iter)?;
+        let round_mint_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let receipt_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        Self::pay_project_mint(
+            program_id,
+            round_info,
+            project_info,
+            Some(project_owner_info),
+            round_mint_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            receipt_info,
+            project_owner_info,
+            token_program_info,
+            system_program_info,
+            rent,
+            account_info_iter.next(),
+        )
+    }
+
+    /// Pays out the share of a project in the vault of another mint of its
+    /// round to `to_info`, `payer_info` pays for the withdraw receipt. When
+    /// the project has a payout account, `payout_info` is that account and
+    /// `to_info` has to be owned by its owner. Without the project owner
+    /// signing, the project needs a payout account unless it is owed nothing.
+    #[allow(clippy::too_many_arguments)]
+    fn pay_project_mint<'a>(
+        program_id: &Pubkey,
+        round_info: &AccountInfo<'a>,
+        project_info: &AccountInfo<'a>,
+        project_owner_info: Option<&AccountInfo<'a>>,
+        round_mint_info: &AccountInfo<'a>,
+        vault_info: &AccountInfo<'a>,
+        vault_owner_info: &AccountInfo<'a>,
+        to_info: &AccountInfo<'a>,
+        receipt_info: &AccountInfo<'a>,
+        payer_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        rent: &Rent,
+        payout_info: Option<&AccountInfo<'a>>,
+    ) -> ProgramResult {
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if !project.withdraw {
+            return Err(QFError::ProjectNotWithdrawn.into());
+        }
+        let Payout { amount, .. } = Self::project_payout(&round, &project)?;
+        let metadata = ProjectMetadata::unpack(&project_info.data.borrow()[Project::LEN..])?;
+        match project_owner_info {
+            Some(project_owner_info) => {
+                if !project_owner_info.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if project.owner != *project_owner_info.key {
+                    return Err(QFError::OwnerMismatch.into());
+                }
+            }
+            None => {
+                if metadata.payout == Pubkey::default() && amount != 0 {
+                    return Err(QFError::PayoutNotSet.into());
+                }
+            }
+        }
+
+        if round_mint_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round_mint = RoundMint::unpack(&round_mint_info.data.borrow())?;
+        if round_mint.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if vault_info.key != &round_mint.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+
+        Self::check_token_account_mint(to_info, &round_mint.mint)?;
+        // the payout account only holds the round mint, shares of other mints
+        // go to a token account of the same owner
+        if metadata.payout != Pubkey::default() {
+            let payout_info = payout_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if payout_info.key != &metadata.payout {
+                return Err(QFError::PayoutMismatch.into());
+            }
+            if payout_info.owner != &spl_token::ID {
+                return Err(QFError::UnexpectedTokenProgramID.into());
+            }
+            let payout = spl_token::state::Account::unpack(&payout_info.data.borrow())?;
+            let to = spl_token::state::Account::unpack(&to_info.data.borrow())?;
+            if to.owner != payout.owner {
+                return Err(QFError::PayoutMismatch.into());
+            }
+        }
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+
+        // the receipt marks the share of this mint as withdrawn
+        let (expected_receipt, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"receipt",
+                &project_info.key.to_bytes(),
+                &round_mint.mint.to_bytes(),
+            ],
+            program_id,
+        );
+        if receipt_info.key != &expected_receipt {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if receipt_info.owner == program_id {
+            return Err(QFError::ProjectAlreadyWithdraw.into());
+        }
+        Self::create_program_account(
+            program_id,
+            receipt_info,
+            payer_info,
+            system_program_info,
+            rent,
+            0,
+            &[
+                b"receipt",
+                &project_info.key.to_bytes(),
+                &round_mint.mint.to_bytes(),
+                &[bump_seed],
+            ],
+        )?;
+
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            payout::vault_share(&round, amount, round_mint.deposited, round_mint.donated)?,
+        )?;
+
+        round_mint.withdrawn_count = round_mint
+            .withdrawn_count
+            .checked_add(1)
+            .ok_or(QFError::MathOverflow)?;
+        RoundMint::pack(round_mint, &mut round_mint_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_withdraw_mint_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let round_mint_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished {
+            return Err(QFError::RoundStatusError.into());
+        }
+
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        if round_mint_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round_mint = RoundMint::unpack(&round_mint_info.data.borrow())?;
+        if round_mint.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if vault_info.key != &round_mint.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+        if round_mint.fee_withdrawn == round.fee_total {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        if to_info.key != &round_mint.fee_recipient {
+            return Err(QFError::FeeRecipientMismatch.into());
+        }
+        Self::check_token_account_mint(to_info, &round_mint.mint)?;
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        let fee = round
+            .fee_total
+            .checked_sub(round_mint.fee_withdrawn)
+            .ok_or(QFError::MathOverflow)?;
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            payout::vault_share(&round, fee, round_mint.deposited, round_mint.donated)?,
+        )?;
+
+        round_mint.fee_withdrawn = round.fee_total;
+        RoundMint::pack(round_mint, &mut round_mint_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_attest(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        trust_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let attestation_info = next_account_info(account_info_iter)?;
+        let issuer_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if !issuer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+        if trust_bps as u64 > BPS_DENOMINATOR {
+            return Err(QFError::InvalidTrustScore.into());
+        }
+
+        let (expected_key, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"attestation",
+                &issuer_info.key.to_bytes(),
+                &wallet_info.key.to_bytes(),
+            ],
+            program_id,
+        );
+        if attestation_info.key != &expected_key {
+            return Err(QFError::AttestationMismatch.into());
+        }
+        // attesting a wallet again only updates its trust score
+        if attestation_info.data_is_empty() {
+            msg!("Create the attestation");
+            Self::create_program_account(
+                program_id,
+                attestation_info,
+                payer_info,
+                system_program_info,
+                rent,
+                Attestation::LEN,
+                &[
+                    b"attestation",
+                    &issuer_info.key.to_bytes(),
+                    &wallet_info.key.to_bytes(),
+                    &[bump_seed],
+                ],
+            )?;
+        }
+        if attestation_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let attestation = Attestation {
+            is_initialized: true,
+            issuer: *issuer_info.key,
+            wallet: *wallet_info.key,
+            trust_bps,
+        };
+        Attestation::pack(attestation, &mut attestation_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_revoke_attestation(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let attestation_info = next_account_info(account_info_iter)?;
+        let issuer_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+
+        if attestation_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let attestation = Attestation::unpack(&attestation_info.data.borrow())?;
+        if attestation.issuer != *issuer_info.key {
+            return Err(QFError::OwnerMismatch.into());
+        }
+        if !issuer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // votes already cast keep their weight, later ones are not matched
+        // as the voter has no trust score anymore
+        msg!("Close the attestation");
+        let lamports = attestation_info.lamports();
+        **attestation_info.lamports.borrow_mut() = 0;
+        **recipient_info.lamports.borrow_mut() = recipient_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(QFError::MathOverflow)?;
+        attestation_info.data.borrow_mut().fill(0);
+
+        Ok(())
+    }
+
+    pub fn process_add_curator(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let curator_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+
+        let (expected_key, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"curator",
+                &round_info.key.to_bytes(),
+                &wallet_info.key.to_bytes(),
+            ],
+            program_id,
+        );
+        if curator_info.key != &expected_key {
+            return Err(QFError::NotCurator.into());
+        }
+        if !curator_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        msg!("Create the curator");
+        Self::create_program_account(
+            program_id,
+            curator_info,
+            payer_info,
+            system_program_info,
+            rent,
+            Curator::LEN,
+            &[
+                b"curator",
+                &round_info.key.to_bytes(),
+                &wallet_info.key.to_bytes(),
+                &[bump_seed],
+            ],
+        )?;
+
+        let curator = Curator {
+            is_initialized: true,
+            round: *round_info.key,
+            wallet: *wallet_info.key,
+        };
+        Curator::pack(curator, &mut curator_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_remove_curator(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let curator_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        if curator_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let curator = Curator::unpack(&curator_info.data.borrow())?;
+        if curator.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+
+        msg!("Close the curator");
+        let lamports = curator_info.lamports();
+        **curator_info.lamports.borrow_mut() = 0;
+        **recipient_info.lamports.borrow_mut() = recipient_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(QFError::MathOverflow)?;
+        curator_info.data.borrow_mut().fill(0);
+
+        Ok(())
+    }
+
+    /// Approves or rejects a pending project, signed by the round owner or a
+    /// curator of the round.
+    pub fn process_review_project(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        status: ProjectStatus,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Ongoing {
+            return Err(QFError::RoundStatusError.into());
+        }
+
+        if round.owner == *authority_info.key {
+            Self::check_authority(&round.owner, authority_info, account_info_iter.as_slice())?;
+        } else {
+            // any other authority needs its curator account
+            if !authority_info.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let curator_info = next_account_info(account_info_iter)?;
+            let (expected_key, _) = Pubkey::find_program_address(
+                &[
+                    b"curator",
+                    &round_info.key.to_bytes(),
+                    &authority_info.key.to_bytes(),
+                ],
+                program_id,
+            );
+            if curator_info.key != &expected_key || curator_info.owner != program_id {
+                return Err(QFError::NotCurator.into());
+            }
+            Curator::unpack(&curator_info.data.borrow()).or(Err(QFError::NotCurator))?;
+        }
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if project.status != ProjectStatus::Pending {
+            return Err(QFError::ProjectNotPending.into());
+        }
+
+        project.status = status;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+
+        if status == ProjectStatus::Approved {
+            round.approved_count = round
+                .approved_count
+                .checked_add(1)
+                .ok_or(QFError::MathOverflow)?;
+            Round::pack(round, &mut round_info.data.borrow_mut())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn process_update_project_metadata(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        metadata: ProjectMetadata,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Ongoing {
+            return Err(QFError::RoundStatusError.into());
+        }
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if project.owner != *owner_info.key {
+            return Err(QFError::OwnerMismatch.into());
+        }
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+        if !metadata.is_valid() {
+            return Err(QFError::InvalidMetadata.into());
+        }
+        Self::check_payout_account(&metadata, &round, account_info_iter)?;
+
+        // the owner pays the rent of a larger account
+        let account_len = Project::account_len(&metadata);
+        let required_lamports = rent
+            .minimum_balance(account_len)
+            .saturating_sub(project_info.lamports());
+        if required_lamports > 0 {
+            msg!("Transfer {} lamports", required_lamports);
+            invoke(
+                &system_instruction::transfer(owner_info.key, project_info.key, required_lamports),
+                &[
+                    owner_info.clone(),
+                    project_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+        project_info.realloc(account_len, false)?;
+        project_info.data.borrow_mut()[Project::LEN..].copy_from_slice(&metadata.pack());
+
+        Ok(())
+    }
+
+    pub fn process_set_round_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let new_owner_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        // the default pubkey withdraws a proposal
+        round.pending_owner = *new_owner_info.key;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_accept_round_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let new_owner_info = next_account_info(account_info_iter)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.pending_owner == Pubkey::default() {
+            return Err(QFError::NoPendingAuthority.into());
+        }
+        Self::check_authority(
+            &round.pending_owner,
+            new_owner_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        round.owner = round.pending_owner;
+        round.pending_owner = Pubkey::default();
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_init_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let config_info = next_account_info(account_info_iter)?;
+        let admin_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        let program_data_info = next_account_info(account_info_iter)?;
+
+        if !admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+
+        // only the upgrade authority of the program picks the admin, nobody
+        // can take the config over before the deployer
+        let (expected_program_data, _) =
+            Pubkey::find_program_address(&[&program_id.to_bytes()], &bpf_loader_upgradeable::id());
+        if program_data_info.key != &expected_program_data
+            || program_data_info.owner != &bpf_loader_upgradeable::id()
+        {
+            return Err(QFError::UpgradeAuthorityMismatch.into());
+        }
+        match limited_deserialize(
+            &program_data_info.data.borrow(),
+            UpgradeableLoaderState::size_of_programdata_metadata() as u64,
+        ) {
+            Ok(UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address: Some(upgrade_authority),
+                ..
+            }) if upgrade_authority == *admin_info.key => {}
+            _ => return Err(QFError::UpgradeAuthorityMismatch.into()),
+        }
+
+        let (expected_key, bump_seed) = Pubkey::find_program_address(&[b"config"], program_id);
+        if config_info.key != &expected_key {
+            return Err(QFError::ConfigMismatch.into());
+        }
+        if !config_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        msg!("Create the config");
+        Self::create_program_account(
+            program_id,
+            config_info,
+            payer_info,
+            system_program_info,
+            rent,
+            Config::LEN,
+            &[b"config", &[bump_seed]],
+        )?;
+
+        let config = Config {
+            is_initialized: true,
+            admin: *admin_info.key,
+        };
+        Config::pack(config, &mut config_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_add_creator(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let config_info = next_account_info(account_info_iter)?;
+        let admin_info = next_account_info(account_info_iter)?;
+        let creator_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+
+        let config = Self::unpack_config(program_id, config_info)?;
+        Self::check_authority(&config.admin, admin_info, account_info_iter.as_slice())?;
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+
+        let (expected_key, bump_seed) =
+            Pubkey::find_program_address(&[b"creator", &wallet_info.key.to_bytes()], program_id);
+        if creator_info.key != &expected_key {
+            return Err(QFError::CreatorNotAllowed.into());
+        }
+        if !creator_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        msg!("Create the creator");
+        Self::create_program_account(
+            program_id,
+            creator_info,
+            payer_info,
+            system_program_info,
+            rent,
+            Creator::LEN,
+            &[b"creator", &wallet_info.key.to_bytes(), &[bump_seed]],
+        )?;
+
+        let creator = Creator {
+            is_initialized: true,
+            wallet: *wallet_info.key,
+        };
+        Creator::pack(creator, &mut creator_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_remove_creator(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let config_info = next_account_info(account_info_iter)?;
+        let admin_info = next_account_info(account_info_iter)?;
+        let creator_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+
+        let config = Self::unpack_config(program_id, config_info)?;
+        Self::check_authority(&config.admin, admin_info, account_info_iter.as_slice())?;
+
+        if creator_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Creator::unpack(&creator_info.data.borrow())?;
+
+        msg!("Close the creator");
+        let lamports = creator_info.lamports();
+        **creator_info.lamports.borrow_mut() = 0;
+        **recipient_info.lamports.borrow_mut() = recipient_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(QFError::MathOverflow)?;
+        creator_info.data.borrow_mut().fill(0);
+
+        Ok(())
+    }
+
+    pub fn process_close_round(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
 
         if round_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
-        let mut round = Round::unpack(&round_info.data.borrow())?;
-        if round.status != RoundStatus::Finished {
-            return Err(QFError::RoundStatusError.into());
+        let round = Round::unpack(&round_info.data.borrow())?;
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+
+        match round.status {
+            RoundStatus::Finished => {
+                if round.withdrawn_count != round.project_count {
+                    return Err(QFError::ProjectNotWithdrawn.into());
+                }
+                if round.fee != 0 {
+                    return Err(QFError::FeeNotWithdrawn.into());
+                }
+                if round.escrowed != 0 {
+                    return Err(QFError::EscrowOutstanding.into());
+                }
+                if payout::refunds_donors(&round) && round.fund != 0 {
+                    return Err(QFError::DonationsNotRefunded.into());
+                }
+            }
+            // nothing is paid out of a cancelled round, it closes once all
+            // donations and votes went back
+            RoundStatus::Cancelled => {
+                if round.fund != 0 {
+                    return Err(QFError::DonationsNotRefunded.into());
+                }
+                if round.votes != 0 {
+                    return Err(QFError::VotesNotRefunded.into());
+                }
+            }
+            _ => return Err(QFError::RoundStatusError.into()),
+        }
+        if vault_info.key != &round.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+        let (expected_vault_owner, _) =
+            Pubkey::find_program_address(&[&round_info.key.to_bytes()], program_id);
+        if vault_owner_info.key != &expected_vault_owner {
+            return Err(QFError::OwnerMismatch.into());
+        }
+        Self::check_token_account_mint(to_info, &round.mint)?;
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        Self::sweep_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            recipient_info,
+            token_program_info,
+        )?;
+
+        // every round mint follows with its vault and a token account of its
+        // mint for the residual, so no vault stays behind without its round
+        for _ in 0..round.mint_count {
+            let round_mint_info = next_account_info(account_info_iter)?;
+            let mint_vault_info = next_account_info(account_info_iter)?;
+            let mint_to_info = next_account_info(account_info_iter)?;
+
+            if round_mint_info.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let round_mint = RoundMint::unpack(&round_mint_info.data.borrow())?;
+            if round_mint.round != *round_info.key {
+                return Err(QFError::RoundMismatch.into());
+            }
+            if mint_vault_info.key != &round_mint.vault {
+                return Err(QFError::VaultMismatch.into());
+            }
+            if round.status == RoundStatus::Finished {
+                if round_mint.withdrawn_count != round.project_count {
+                    return Err(QFError::ProjectNotWithdrawn.into());
+                }
+                if round_mint.fee_withdrawn != round.fee_total {
+                    return Err(QFError::FeeNotWithdrawn.into());
+                }
+            }
+            Self::check_token_account_mint(mint_to_info, &round_mint.mint)?;
+
+            Self::sweep_vault(
+                program_id,
+                round_info,
+                mint_vault_info,
+                vault_owner_info,
+                mint_to_info,
+                recipient_info,
+                token_program_info,
+            )?;
+
+            msg!("Close the round mint");
+            let lamports = round_mint_info.lamports();
+            **round_mint_info.lamports.borrow_mut() = 0;
+            **recipient_info.lamports.borrow_mut() = recipient_info
+                .lamports()
+                .checked_add(lamports)
+                .ok_or(QFError::MathOverflow)?;
+            round_mint_info.data.borrow_mut().fill(0);
+        }
+
+        msg!("Close the round");
+        let lamports = round_info.lamports();
+        **round_info.lamports.borrow_mut() = 0;
+        **recipient_info.lamports.borrow_mut() = recipient_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(QFError::MathOverflow)?;
+        round_info.data.borrow_mut().fill(0);
+
+        Ok(())
+    }
+
+    pub fn process_close_voter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let voter_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+        let round_info = next_account_info(account_info_iter)?;
+
+        if voter_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (expected_voter, _) = Pubkey::find_program_address(
+            &[
+                b"voter",
+                &project_info.key.to_bytes(),
+                &wallet_info.key.to_bytes(),
+            ],
+            program_id,
+        );
+        if voter_info.key != &expected_voter {
+            return Err(QFError::VoterMismatch.into());
         }
-        if round.fee == 0 {
-            return Err(ProgramError::InsufficientFunds);
+        let voter = Voter::unpack(&voter_info.data.borrow())?;
+        if recipient_info.key != &voter.payer {
+            return Err(QFError::PayerMismatch.into());
         }
 
-        if owner_info.key != &round.owner {
-            return Err(QFError::OwnerMismatch.into());
+        // votes are no longer needed once the project has withdrawn or they
+        // went back in a cancelled round, a closed project did either before
+        if project_info.owner == program_id {
+            let project = Project::unpack_account(&project_info.data.borrow())?;
+            if !project.withdraw {
+                Self::check_cancelled_round(program_id, round_info, &project)?;
+                if voter.votes != 0 {
+                    return Err(QFError::VotesNotRefunded.into());
+                }
+            }
+        } else if !project_info.data_is_empty() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        msg!("Close the voter");
+        let lamports = voter_info.lamports();
+        **voter_info.lamports.borrow_mut() = 0;
+        **recipient_info.lamports.borrow_mut() = recipient_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(QFError::MathOverflow)?;
+        voter_info.data.borrow_mut().fill(0);
+
+        Ok(())
+    }
+
+    pub fn process_close_project(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let project_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+        let round_info = next_account_info(account_info_iter)?;
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
         }
+        let project = Project::unpack_account(&project_info.data.borrow())?;
         if !owner_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
+        if project.owner != *owner_info.key {
+            return Err(QFError::OwnerMismatch.into());
+        }
+        if !project.withdraw {
+            Self::check_cancelled_round(program_id, round_info, &project)?;
+            if project.votes != 0 {
+                return Err(QFError::VotesNotRefunded.into());
+            }
+        } else {
+            Self::check_mint_receipts(
+                program_id,
+                round_info,
+                project_info,
+                &project,
+                account_info_iter,
+            )?;
+        }
+        if project.claimed != project.escrowed {
+            return Err(QFError::EscrowOutstanding.into());
+        }
+        if recipient_info.key != &project.payer {
+            return Err(QFError::PayerMismatch.into());
+        }
+
+        msg!("Close the project");
+        let lamports = project_info.lamports();
+        **project_info.lamports.borrow_mut() = 0;
+        **recipient_info.lamports.borrow_mut() = recipient_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(QFError::MathOverflow)?;
+        project_info.data.borrow_mut().fill(0);
+
+        Ok(())
+    }
 
+    pub fn process_claim_vested(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let project_owner_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
         if vault_info.key != &round.vault {
             return Err(QFError::VaultMismatch.into());
         }
 
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if !project_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if project.owner != *project_owner_info.key {
+            return Err(QFError::OwnerMismatch.into());
+        }
+        let metadata = ProjectMetadata::unpack(&project_info.data.borrow()[Project::LEN..])?;
+        if metadata.payout != Pubkey::default() && metadata.payout != *to_info.key {
+            return Err(QFError::PayoutMismatch.into());
+        }
+
+        Self::check_token_account_mint(to_info, &round.mint)?;
+
         if token_program_info.key != &spl_token::ID {
             return Err(QFError::UnexpectedTokenProgramID.into());
         }
 
-        let seeds: &[&[_]] = &[
-            &round_info.key.to_bytes(),
-            &[Pubkey::find_program_address(&[&round_info.key.to_bytes()], &program_id).1],
-        ];
+        let vested = payout::vested_payout(&round, &project, clock.unix_timestamp)?;
+        let amount = vested
+            .checked_sub(project.claimed)
+            .ok_or(QFError::MathOverflow)?;
+
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            payout::vault_share(&round, amount, round.deposited, round.donated)?,
+        )?;
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                &token_program_info.key,
-                &vault_info.key,
-                &to_info.key,
-                &vault_owner_info.key,
-                &[&vault_owner_info.key],
-                round.fee,
-            )?,
-            &[
-                vault_info.clone(),
-                to_info.clone(),
-                vault_owner_info.clone(),
-                token_program_info.clone(),
-            ],
-            &[&seeds],
+        project.claimed = vested;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+
+        round.escrowed = round
+            .escrowed
+            .checked_sub(amount)
+            .ok_or(QFError::MathOverflow)?;
+        round.paid_out = round
+            .paid_out
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_claw_back(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let project_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+        if !round.is_vesting() {
+            return Err(QFError::NotVestingRound.into());
+        }
+        if vault_info.key != &round.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+
+        if project_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut project = Project::unpack_account(&project_info.data.borrow())?;
+        if project.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if !project.withdraw {
+            return Err(QFError::ProjectNotWithdrawn.into());
+        }
+        if project.flagged {
+            return Err(QFError::ProjectFlagged.into());
+        }
+
+        Self::check_token_account_mint(to_info, &round.mint)?;
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+
+        // what vested so far stays claimable by the project
+        let vested = payout::vested_payout(&round, &project, clock.unix_timestamp)?;
+        let unvested = project
+            .escrowed
+            .checked_sub(vested)
+            .ok_or(QFError::MathOverflow)?;
+
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            to_info,
+            token_program_info,
+            payout::vault_share(&round, unvested, round.deposited, round.donated)?,
         )?;
 
-        round.fee = 0;
+        project.escrowed = vested;
+        project.flagged = true;
+        Project::pack_account(project, &mut project_info.data.borrow_mut())?;
+
+        round.escrowed = round
+            .escrowed
+            .checked_sub(unvested)
+            .ok_or(QFError::MathOverflow)?;
+        round.paid_out = round
+            .paid_out
+            .checked_add(unvested)
+            .ok_or(QFError::MathOverflow)?;
+        Round::pack(round, &mut round_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_roll_over(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let round_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let vault_owner_info = next_account_info(account_info_iter)?;
+        let successor_info = next_account_info(account_info_iter)?;
+        let successor_vault_info = next_account_info(account_info_iter)?;
+        let donor_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        let clock = &Clock::from_account_info(next_account_info(account_info_iter)?)?;
+
+        if round_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut round = Round::unpack(&round_info.data.borrow())?;
+        if round.status != RoundStatus::Finished
+            || payout::empty_round_policy(&round) != EmptyRoundPolicy::RollOver
+        {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if !payout::is_empty_round(&round) {
+            return Err(QFError::RoundNotEmpty.into());
+        }
+        Self::check_authority(&round.owner, owner_info, account_info_iter.as_slice())?;
+        if vault_info.key != &round.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+
+        if successor_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut successor = Round::unpack(&successor_info.data.borrow())?;
+        if successor.status != RoundStatus::Ongoing {
+            return Err(QFError::RoundStatusError.into());
+        }
+        if clock.unix_timestamp < successor.start_time {
+            return Err(QFError::RoundNotStarted.into());
+        }
+        if clock.unix_timestamp >= successor.end_time {
+            return Err(QFError::RoundEnded.into());
+        }
+        if successor.mint != round.mint {
+            return Err(QFError::MintMismatch.into());
+        }
+        if successor_vault_info.key != &successor.vault {
+            return Err(QFError::VaultMismatch.into());
+        }
+
+        if token_program_info.key != &spl_token::ID {
+            return Err(QFError::UnexpectedTokenProgramID.into());
+        }
+        if system_program_info.key != &system_program::ID {
+            return Err(QFError::UnexpectedSystemProgramID.into());
+        }
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // the pool is a donation of the round owner to the successor, which
+        // goes back to the owner if the successor refunds its donors
+        let mut donor = Self::load_donor(
+            program_id,
+            successor_info,
+            donor_info,
+            owner_info,
+            payer_info,
+            &round.mint,
+            system_program_info,
+            rent,
+        )?;
+
+        // the round only holds its own mint, the pool is all in its vault
+        let amount = round.fund;
+        Self::transfer_from_vault(
+            program_id,
+            round_info,
+            vault_info,
+            vault_owner_info,
+            successor_vault_info,
+            token_program_info,
+            amount,
+        )?;
+
+        round.fund = 0;
+        Self::remove_deposit(&mut round, None, amount, true)?;
         Round::pack(round, &mut round_info.data.borrow_mut())?;
 
+        successor.fund = successor
+            .fund
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        Self::add_deposit(&mut successor, None, amount, true)?;
+        Round::pack(successor, &mut successor_info.data.borrow_mut())?;
+
+        donor.amount = donor
+            .amount
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        donor.value = donor
+            .value
+            .checked_add(amount)
+            .ok_or(QFError::MathOverflow)?;
+        Donor::pack(donor, &mut donor_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_close_donor(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let donor_info = next_account_info(account_info_iter)?;
+        let round_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+
+        if donor_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let donor = Donor::unpack(&donor_info.data.borrow())?;
+        if donor.round != *round_info.key {
+            return Err(QFError::RoundMismatch.into());
+        }
+        if authority_info.key != &donor.authority {
+            return Err(QFError::DonorMismatch.into());
+        }
+        Self::check_authority(
+            &donor.authority,
+            authority_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        // a donation is no longer needed once it went back to the donor, or
+        // once the round is closed and nothing can be refunded anymore
+        if round_info.owner == program_id {
+            let round = Round::unpack(&round_info.data.borrow())?;
+            match round.status {
+                RoundStatus::Finished | RoundStatus::Cancelled => {
+                    if donor.amount != 0 {
+                        return Err(QFError::DonationsNotRefunded.into());
+                    }
+                }
+                _ => return Err(QFError::RoundStatusError.into()),
+            }
+        } else if !round_info.data_is_empty() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        msg!("Close the donor");
+        let lamports = donor_info.lamports();
+        **donor_info.lamports.borrow_mut() = 0;
+        **recipient_info.lamports.borrow_mut() = recipient_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(QFError::MathOverflow)?;
+        donor_info.data.borrow_mut().fill(0);
+
         Ok(())
     }
 
+    /// Checks the program config account and unpacks it.
+    fn unpack_config(
+        program_id: &Pubkey,
+        config_info: &AccountInfo,
+    ) -> Result<Config, ProgramError> {
+        let (expected_key, _) = Pubkey::find_program_address(&[b"config"], program_id);
+        if config_info.key != &expected_key {
+            return Err(QFError::ConfigMismatch.into());
+        }
+        if config_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Config::unpack(&config_info.data.borrow())
+    }
+
     /// Processes an [Instruction](enum.Instruction.html).
     pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
         let instruction = QFInstruction::unpack(input)?;
         match instruction {
-            QFInstruction::StartRound => {
+            QFInstruction::StartRound(config) => {
                 msg!("Instruction: StartRound");
-                Self::process_start_round(program_id, accounts)
+                Self::process_start_round(program_id, accounts, config)
             }
             QFInstruction::Donate { amount, decimals } => {
                 msg!("Instruction: Donate");
                 Self::process_donate(program_id, accounts, amount, decimals)
             }
-            QFInstruction::RegisterProject => {
+            QFInstruction::RegisterProject(metadata) => {
                 msg!("Instruction: RegisterProject");
-                Self::process_register_project(program_id, accounts)
+                Self::process_register_project(program_id, accounts, metadata)
             }
             QFInstruction::InitVoter => {
                 msg!("Instruction: InitVoter");
@@ -598,6 +3786,146 @@ impl Processor {
                 msg!("Instruction: WithdrawFee");
                 Self::process_withdraw_fee(program_id, accounts)
             }
+            QFInstruction::FinalizeRound => {
+                msg!("Instruction: FinalizeRound");
+                Self::process_finalize_round(program_id, accounts)
+            }
+            QFInstruction::SettleMatching => {
+                msg!("Instruction: SettleMatching");
+                Self::process_settle_matching(program_id, accounts)
+            }
+            QFInstruction::InitVoterPair => {
+                msg!("Instruction: InitVoterPair");
+                Self::process_init_voter_pair(program_id, accounts)
+            }
+            QFInstruction::AccumulateVoterPair => {
+                msg!("Instruction: AccumulateVoterPair");
+                Self::process_accumulate_voter_pair(program_id, accounts)
+            }
+            QFInstruction::DistributeVoterPair => {
+                msg!("Instruction: DistributeVoterPair");
+                Self::process_distribute_voter_pair(program_id, accounts)
+            }
+            QFInstruction::CancelRound => {
+                msg!("Instruction: CancelRound");
+                Self::process_cancel_round(program_id, accounts)
+            }
+            QFInstruction::RefundDonation => {
+                msg!("Instruction: RefundDonation");
+                Self::process_refund_donation(program_id, accounts)
+            }
+            QFInstruction::RefundVote => {
+                msg!("Instruction: RefundVote");
+                Self::process_refund_vote(program_id, accounts)
+            }
+            QFInstruction::InitPriceFeed { price } => {
+                msg!("Instruction: InitPriceFeed");
+                Self::process_init_price_feed(program_id, accounts, price)
+            }
+            QFInstruction::SetPrice { price } => {
+                msg!("Instruction: SetPrice");
+                Self::process_set_price(program_id, accounts, price)
+            }
+            QFInstruction::AddMint => {
+                msg!("Instruction: AddMint");
+                Self::process_add_mint(program_id, accounts)
+            }
+            QFInstruction::WithdrawMint => {
+                msg!("Instruction: WithdrawMint");
+                Self::process_withdraw_mint(program_id, accounts)
+            }
+            QFInstruction::WithdrawMintFee => {
+                msg!("Instruction: WithdrawMintFee");
+                Self::process_withdraw_mint_fee(program_id, accounts)
+            }
+            QFInstruction::DonateNative { amount } => {
+                msg!("Instruction: DonateNative");
+                Self::process_donate_native(program_id, accounts, amount)
+            }
+            QFInstruction::VoteNative { amount } => {
+                msg!("Instruction: VoteNative");
+                Self::process_vote_native(program_id, accounts, amount)
+            }
+            QFInstruction::Attest { trust_bps } => {
+                msg!("Instruction: Attest");
+                Self::process_attest(program_id, accounts, trust_bps)
+            }
+            QFInstruction::RevokeAttestation => {
+                msg!("Instruction: RevokeAttestation");
+                Self::process_revoke_attestation(program_id, accounts)
+            }
+            QFInstruction::AddCurator => {
+                msg!("Instruction: AddCurator");
+                Self::process_add_curator(program_id, accounts)
+            }
+            QFInstruction::RemoveCurator => {
+                msg!("Instruction: RemoveCurator");
+                Self::process_remove_curator(program_id, accounts)
+            }
+            QFInstruction::ApproveProject => {
+                msg!("Instruction: ApproveProject");
+                Self::process_review_project(program_id, accounts, ProjectStatus::Approved)
+            }
+            QFInstruction::RejectProject => {
+                msg!("Instruction: RejectProject");
+                Self::process_review_project(program_id, accounts, ProjectStatus::Rejected)
+            }
+            QFInstruction::UpdateProjectMetadata(metadata) => {
+                msg!("Instruction: UpdateProjectMetadata");
+                Self::process_update_project_metadata(program_id, accounts, metadata)
+            }
+            QFInstruction::SetRoundAuthority => {
+                msg!("Instruction: SetRoundAuthority");
+                Self::process_set_round_authority(program_id, accounts)
+            }
+            QFInstruction::AcceptRoundAuthority => {
+                msg!("Instruction: AcceptRoundAuthority");
+                Self::process_accept_round_authority(program_id, accounts)
+            }
+            QFInstruction::InitConfig => {
+                msg!("Instruction: InitConfig");
+                Self::process_init_config(program_id, accounts)
+            }
+            QFInstruction::AddCreator => {
+                msg!("Instruction: AddCreator");
+                Self::process_add_creator(program_id, accounts)
+            }
+            QFInstruction::RemoveCreator => {
+                msg!("Instruction: RemoveCreator");
+                Self::process_remove_creator(program_id, accounts)
+            }
+            QFInstruction::CloseRound => {
+                msg!("Instruction: CloseRound");
+                Self::process_close_round(program_id, accounts)
+            }
+            QFInstruction::CloseVoter => {
+                msg!("Instruction: CloseVoter");
+                Self::process_close_voter(program_id, accounts)
+            }
+            QFInstruction::CloseProject => {
+                msg!("Instruction: CloseProject");
+                Self::process_close_project(program_id, accounts)
+            }
+            QFInstruction::DistributeToProject => {
+                msg!("Instruction: DistributeToProject");
+                Self::process_distribute_to_project(program_id, accounts)
+            }
+            QFInstruction::ClaimVested => {
+                msg!("Instruction: ClaimVested");
+                Self::process_claim_vested(program_id, accounts)
+            }
+            QFInstruction::ClawBack => {
+                msg!("Instruction: ClawBack");
+                Self::process_claw_back(program_id, accounts)
+            }
+            QFInstruction::RollOver => {
+                msg!("Instruction: RollOver");
+                Self::process_roll_over(program_id, accounts)
+            }
+            QFInstruction::CloseDonor => {
+                msg!("Instruction: CloseDonor");
+                Self::process_close_donor(program_id, accounts)
+            }
         }
     }
 }
@@ -619,6 +3947,62 @@ impl PrintProgramError for QFError {
                 msg!("unexpected asoociated token account program id")
             }
             QFError::VoterMismatch => msg!("voter mismatch"),
+            QFError::InvalidRoundTime => msg!("invalid round time"),
+            QFError::RoundNotStarted => msg!("round has not started"),
+            QFError::RoundEnded => msg!("round has ended"),
+            QFError::RegistrationClosed => msg!("project registration has closed"),
+            QFError::RoundNotEnded => msg!("round has not reached its end time"),
+            QFError::InvalidFeeRate => msg!("fee rate exceeds the maximum"),
+            QFError::FeeRecipientMismatch => msg!("fee recipient does not match"),
+            QFError::InvalidMatchingCap => msg!("invalid matching cap"),
+            QFError::MatchingNotSettled => msg!("matching of the round is not settled"),
+            QFError::MatchingAlreadySettled => msg!("matching of the round has already settled"),
+            QFError::IncompleteProjects => msg!("projects of the round are incomplete"),
+            QFError::InvalidPairwiseThreshold => msg!("pairwise threshold must be positive"),
+            QFError::MatchingAlgorithmMismatch => {
+                msg!("matching algorithm of the round does not match")
+            }
+            QFError::VoterPairMismatch => msg!("voter pair does not match"),
+            QFError::PairNotOverlapped => msg!("voters of the pair do not both vote the project"),
+            QFError::PairAlreadyProcessed => {
+                msg!("voter pair has already processed the project")
+            }
+            QFError::PairwiseNotAccumulated => {
+                msg!("voter pairs of the round are not all accumulated")
+            }
+            QFError::DonorMismatch => msg!("donor mismatch"),
+            QFError::RoundAlreadyPaidOut => msg!("round has already paid out"),
+            QFError::MintMismatch => msg!("mint does not match"),
+            QFError::InvalidPrice => msg!("price must be positive"),
+            QFError::PriceFeedMismatch => msg!("price feed does not match"),
+            QFError::MintAlreadyAccepted => msg!("mint is already accepted by the round"),
+            QFError::ProjectNotWithdrawn => msg!("project has not withdrawn from the round vault"),
+            QFError::AttestationMismatch => msg!("attestation does not match"),
+            QFError::InvalidTrustScore => msg!("trust score exceeds 100%"),
+            QFError::ProjectNotApproved => msg!("project is not approved to receive votes"),
+            QFError::ProjectNotPending => msg!("project has already been reviewed"),
+            QFError::NotCurator => msg!("signer is neither the round owner nor a curator"),
+            QFError::InvalidMetadata => msg!("project metadata exceeds its maximum length"),
+            QFError::PayoutMismatch => msg!("payout account of the project does not match"),
+            QFError::NoPendingAuthority => msg!("round has no pending authority"),
+            QFError::ConfigMismatch => msg!("config account does not match"),
+            QFError::CreatorNotAllowed => msg!("round owner is not an allowed creator"),
+            QFError::MathOverflow => msg!("math operation overflowed"),
+            QFError::RoundNotEmpty => msg!("round has matched votes"),
+            QFError::FeeNotWithdrawn => msg!("fee of the round is not withdrawn"),
+            QFError::DonationsNotRefunded => msg!("donations of the round are not refunded"),
+            QFError::RoundHasMints => msg!("round has accepted other mints"),
+            QFError::PayerMismatch => msg!("recipient is not the payer of the account"),
+            QFError::PayoutNotSet => msg!("project has no payout account"),
+            QFError::InvalidVestingSchedule => msg!("invalid vesting schedule"),
+            QFError::NotVestingRound => msg!("round has no vesting schedule"),
+            QFError::ProjectFlagged => msg!("project is already flagged"),
+            QFError::EscrowOutstanding => msg!("vesting escrow is not settled"),
+            QFError::MintNotAllowed => msg!("vesting round only accepts its own mint"),
+            QFError::UpgradeAuthorityMismatch => {
+                msg!("signer is not the upgrade authority of the program")
+            }
+            QFError::VotesNotRefunded => msg!("votes of the round are not refunded"),
         }
     }
 }
@@ -1,25 +1,88 @@
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use num_enum::TryFromPrimitive;
 use solana_program::{
+    clock::UnixTimestamp,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
 use spl_math::uint::U256;
+use std::convert::TryInto;
+
+/// Denominator of rates expressed in basis points
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Maximum fee rate a round can charge, 10%
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Scale of prices, a price is the amount of the round mint one unit of
+/// another mint is worth times `PRICE_SCALE`
+pub const PRICE_SCALE: u64 = 1_000_000_000;
+
+/// Maximum length in bytes of the name of a project
+pub const MAX_PROJECT_NAME_LEN: usize = 64;
+
+/// Maximum length in bytes of a URI in the metadata of a project
+pub const MAX_PROJECT_URI_LEN: usize = 200;
 
 /// Round status
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, TryFromPrimitive)]
 pub enum RoundStatus {
+    #[default]
     Uninitialized,
     Ongoing,
     Finished,
+    Cancelled,
 }
 
-impl Default for RoundStatus {
-    fn default() -> Self {
-        RoundStatus::Uninitialized
-    }
+/// Project status, projects of a curated round wait for approval before they
+/// can receive votes
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, TryFromPrimitive)]
+pub enum ProjectStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Fee policy, which part of a project's payout the fee is charged on
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, TryFromPrimitive)]
+pub enum FeePolicy {
+    #[default]
+    Total,
+    MatchingOnly,
+    ContributionsOnly,
+}
+
+/// Matching algorithm, how the matching pool is split among projects
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, TryFromPrimitive)]
+pub enum MatchingAlgorithm {
+    /// classic quadratic funding, (sum of sqrt(votes))^2
+    #[default]
+    Quadratic,
+    /// pairwise coordination subsidy, the matching between every two voters is
+    /// discounted by how much they fund the same projects
+    Pairwise,
+}
+
+/// Empty round policy, what happens to the matching pool of a round in which
+/// no vote is matched
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, TryFromPrimitive)]
+pub enum EmptyRoundPolicy {
+    /// donors get their donations back with `RefundDonation`
+    #[default]
+    RefundDonors,
+    /// the pool is split equally among the approved projects, donors are
+    /// refunded when no project is approved
+    SplitEqually,
+    /// the round owner moves the pool into an ongoing round of the same mint
+    /// with `RollOver`, where it counts as a donation of the owner
+    RollOver,
 }
 
 /// Round
@@ -28,10 +91,78 @@ impl Default for RoundStatus {
 pub struct Round {
     pub status: RoundStatus,
     pub fund: u64,
+    /// Fee charged and not withdrawn yet. It is a single total of the fee on
+    /// the base chosen by the fee policy, under `FeePolicy::Total` the fee on
+    /// contributions and on matching is not kept apart.
     pub fee: u64,
     pub vault: Pubkey,
     pub owner: Pubkey,
     pub area: U256,
+    pub start_time: UnixTimestamp,
+    pub end_time: UnixTimestamp,
+    pub registration_end_time: UnixTimestamp,
+    pub fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub fee_policy: FeePolicy,
+    pub matching_cap_bps: u16,
+    pub project_count: u64,
+    pub matching_settled: bool,
+    pub matching_algorithm: MatchingAlgorithm,
+    pub pairwise_threshold: u64,
+    pub pair_count: u64,
+    pub pairs_accumulated: u64,
+    pub pairs_distributed: u64,
+    pub pairwise_area: U256,
+    pub paid_out: u64,
+    pub mint: Pubkey,
+    pub votes: u64,
+    pub deposited: u64,
+    /// Fee charged over the whole round, the same single total as `fee`
+    pub fee_total: u64,
+    pub identity_issuer: Pubkey,
+    pub curated: bool,
+    /// Owner proposed by the current owner, taking over once it accepts
+    pub pending_owner: Pubkey,
+    pub empty_round_policy: EmptyRoundPolicy,
+    pub approved_count: u64,
+    pub withdrawn_count: u64,
+    /// Number of mints accepted with `AddMint` besides the round mint
+    pub mint_count: u64,
+    /// Seconds after the round finished before matching funds start vesting
+    pub vesting_cliff: UnixTimestamp,
+    /// Seconds after the round finished until matching funds are fully
+    /// vested, zero pays them out right away
+    pub vesting_duration: UnixTimestamp,
+    /// Value held in the vesting escrows of the projects
+    pub escrowed: u64,
+    /// Number of projects whose matching `SettleMatching` has settled
+    pub settled_count: u64,
+    /// Number of settled projects which receive exactly the matching cap
+    pub capped_count: u64,
+    /// Matching area of the capped projects
+    pub capped_area: U256,
+    /// Matching area of the last settled project, projects settle by matching
+    /// area descending then by index
+    pub last_settled_area: U256,
+    /// Index of the last settled project
+    pub last_settled_index: u64,
+    /// Donations in the vault of the round mint which are not refunded
+    pub donated: u64,
+    /// Time the round finished with `EndRound` or `FinalizeRound`, vesting
+    /// starts from it
+    pub finished_time: UnixTimestamp,
+}
+impl Round {
+    /// Whether votes of the round need an attestation of the identity issuer
+    /// to be matched
+    pub fn is_identity_gated(&self) -> bool {
+        self.identity_issuer != Pubkey::default()
+    }
+
+    /// Whether matching funds of the round vest in an escrow of each project
+    pub fn is_vesting(&self) -> bool {
+        self.vesting_duration != 0
+    }
 }
 impl Sealed for Round {}
 impl IsInitialized for Round {
@@ -40,10 +171,57 @@ impl IsInitialized for Round {
     }
 }
 impl Pack for Round {
-    const LEN: usize = 113;
+    const LEN: usize = 530;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, 113];
-        let (status, fund, fee, vault, owner, area) = array_refs![src, 1, 8, 8, 32, 32, 32];
+        let src = array_ref![src, 0, 530];
+        let (
+            status,
+            fund,
+            fee,
+            vault,
+            owner,
+            area,
+            start_time,
+            end_time,
+            registration_end_time,
+            fee_bps,
+            fee_recipient,
+            fee_policy,
+            matching_cap_bps,
+            project_count,
+            matching_settled,
+            matching_algorithm,
+            pairwise_threshold,
+            pair_count,
+            pairs_accumulated,
+            pairs_distributed,
+            pairwise_area,
+            paid_out,
+            mint,
+            votes,
+            deposited,
+            fee_total,
+            identity_issuer,
+            curated,
+            pending_owner,
+            empty_round_policy,
+            approved_count,
+            withdrawn_count,
+            mint_count,
+            vesting_cliff,
+            vesting_duration,
+            escrowed,
+            settled_count,
+            capped_count,
+            capped_area,
+            last_settled_area,
+            last_settled_index,
+            donated,
+            finished_time,
+        ) = array_refs![
+            src, 1, 8, 8, 32, 32, 32, 8, 8, 8, 2, 32, 1, 2, 8, 1, 1, 8, 8, 8, 8, 32, 8, 32, 8, 8,
+            8, 32, 1, 32, 1, 8, 8, 8, 8, 8, 8, 8, 8, 32, 32, 8, 8, 8
+        ];
         Ok(Round {
             status: RoundStatus::try_from_primitive(status[0])
                 .or(Err(ProgramError::InvalidAccountData))?,
@@ -52,12 +230,106 @@ impl Pack for Round {
             vault: Pubkey::new_from_array(*vault),
             owner: Pubkey::new_from_array(*owner),
             area: U256::from_little_endian(area),
+            start_time: i64::from_le_bytes(*start_time),
+            end_time: i64::from_le_bytes(*end_time),
+            registration_end_time: i64::from_le_bytes(*registration_end_time),
+            fee_bps: u16::from_le_bytes(*fee_bps),
+            fee_recipient: Pubkey::new_from_array(*fee_recipient),
+            fee_policy: FeePolicy::try_from_primitive(fee_policy[0])
+                .or(Err(ProgramError::InvalidAccountData))?,
+            matching_cap_bps: u16::from_le_bytes(*matching_cap_bps),
+            project_count: u64::from_le_bytes(*project_count),
+            matching_settled: match matching_settled {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            matching_algorithm: MatchingAlgorithm::try_from_primitive(matching_algorithm[0])
+                .or(Err(ProgramError::InvalidAccountData))?,
+            pairwise_threshold: u64::from_le_bytes(*pairwise_threshold),
+            pair_count: u64::from_le_bytes(*pair_count),
+            pairs_accumulated: u64::from_le_bytes(*pairs_accumulated),
+            pairs_distributed: u64::from_le_bytes(*pairs_distributed),
+            pairwise_area: U256::from_little_endian(pairwise_area),
+            paid_out: u64::from_le_bytes(*paid_out),
+            mint: Pubkey::new_from_array(*mint),
+            votes: u64::from_le_bytes(*votes),
+            deposited: u64::from_le_bytes(*deposited),
+            fee_total: u64::from_le_bytes(*fee_total),
+            identity_issuer: Pubkey::new_from_array(*identity_issuer),
+            curated: match curated {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            pending_owner: Pubkey::new_from_array(*pending_owner),
+            empty_round_policy: EmptyRoundPolicy::try_from_primitive(empty_round_policy[0])
+                .or(Err(ProgramError::InvalidAccountData))?,
+            approved_count: u64::from_le_bytes(*approved_count),
+            withdrawn_count: u64::from_le_bytes(*withdrawn_count),
+            mint_count: u64::from_le_bytes(*mint_count),
+            vesting_cliff: i64::from_le_bytes(*vesting_cliff),
+            vesting_duration: i64::from_le_bytes(*vesting_duration),
+            escrowed: u64::from_le_bytes(*escrowed),
+            settled_count: u64::from_le_bytes(*settled_count),
+            capped_count: u64::from_le_bytes(*capped_count),
+            capped_area: U256::from_little_endian(capped_area),
+            last_settled_area: U256::from_little_endian(last_settled_area),
+            last_settled_index: u64::from_le_bytes(*last_settled_index),
+            donated: u64::from_le_bytes(*donated),
+            finished_time: i64::from_le_bytes(*finished_time),
         })
     }
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, 113];
-        let (status_dst, fund_dst, fee_dst, vault_dst, owner_dst, area_dst) =
-            mut_array_refs![dst, 1, 8, 8, 32, 32, 32];
+        let dst = array_mut_ref![dst, 0, 530];
+        let (
+            status_dst,
+            fund_dst,
+            fee_dst,
+            vault_dst,
+            owner_dst,
+            area_dst,
+            start_time_dst,
+            end_time_dst,
+            registration_end_time_dst,
+            fee_bps_dst,
+            fee_recipient_dst,
+            fee_policy_dst,
+            matching_cap_bps_dst,
+            project_count_dst,
+            matching_settled_dst,
+            matching_algorithm_dst,
+            pairwise_threshold_dst,
+            pair_count_dst,
+            pairs_accumulated_dst,
+            pairs_distributed_dst,
+            pairwise_area_dst,
+            paid_out_dst,
+            mint_dst,
+            votes_dst,
+            deposited_dst,
+            fee_total_dst,
+            identity_issuer_dst,
+            curated_dst,
+            pending_owner_dst,
+            empty_round_policy_dst,
+            approved_count_dst,
+            withdrawn_count_dst,
+            mint_count_dst,
+            vesting_cliff_dst,
+            vesting_duration_dst,
+            escrowed_dst,
+            settled_count_dst,
+            capped_count_dst,
+            capped_area_dst,
+            last_settled_area_dst,
+            last_settled_index_dst,
+            donated_dst,
+            finished_time_dst,
+        ) = mut_array_refs![
+            dst, 1, 8, 8, 32, 32, 32, 8, 8, 8, 2, 32, 1, 2, 8, 1, 1, 8, 8, 8, 8, 32, 8, 32, 8, 8,
+            8, 32, 1, 32, 1, 8, 8, 8, 8, 8, 8, 8, 8, 32, 32, 8, 8, 8
+        ];
         let &Round {
             status,
             fund,
@@ -65,6 +337,43 @@ impl Pack for Round {
             ref owner,
             ref vault,
             area,
+            start_time,
+            end_time,
+            registration_end_time,
+            fee_bps,
+            ref fee_recipient,
+            fee_policy,
+            matching_cap_bps,
+            project_count,
+            matching_settled,
+            matching_algorithm,
+            pairwise_threshold,
+            pair_count,
+            pairs_accumulated,
+            pairs_distributed,
+            pairwise_area,
+            paid_out,
+            ref mint,
+            votes,
+            deposited,
+            fee_total,
+            ref identity_issuer,
+            curated,
+            ref pending_owner,
+            empty_round_policy,
+            approved_count,
+            withdrawn_count,
+            mint_count,
+            vesting_cliff,
+            vesting_duration,
+            escrowed,
+            settled_count,
+            capped_count,
+            capped_area,
+            last_settled_area,
+            last_settled_index,
+            donated,
+            finished_time,
         } = self;
         status_dst[0] = status as u8;
         *fund_dst = fund.to_le_bytes();
@@ -72,6 +381,43 @@ impl Pack for Round {
         owner_dst.copy_from_slice(owner.as_ref());
         vault_dst.copy_from_slice(vault.as_ref());
         area.to_little_endian(area_dst);
+        *start_time_dst = start_time.to_le_bytes();
+        *end_time_dst = end_time.to_le_bytes();
+        *registration_end_time_dst = registration_end_time.to_le_bytes();
+        *fee_bps_dst = fee_bps.to_le_bytes();
+        fee_recipient_dst.copy_from_slice(fee_recipient.as_ref());
+        fee_policy_dst[0] = fee_policy as u8;
+        *matching_cap_bps_dst = matching_cap_bps.to_le_bytes();
+        *project_count_dst = project_count.to_le_bytes();
+        matching_settled_dst[0] = matching_settled as u8;
+        matching_algorithm_dst[0] = matching_algorithm as u8;
+        *pairwise_threshold_dst = pairwise_threshold.to_le_bytes();
+        *pair_count_dst = pair_count.to_le_bytes();
+        *pairs_accumulated_dst = pairs_accumulated.to_le_bytes();
+        *pairs_distributed_dst = pairs_distributed.to_le_bytes();
+        pairwise_area.to_little_endian(pairwise_area_dst);
+        *paid_out_dst = paid_out.to_le_bytes();
+        mint_dst.copy_from_slice(mint.as_ref());
+        *votes_dst = votes.to_le_bytes();
+        *deposited_dst = deposited.to_le_bytes();
+        *fee_total_dst = fee_total.to_le_bytes();
+        identity_issuer_dst.copy_from_slice(identity_issuer.as_ref());
+        curated_dst[0] = curated as u8;
+        pending_owner_dst.copy_from_slice(pending_owner.as_ref());
+        empty_round_policy_dst[0] = empty_round_policy as u8;
+        *approved_count_dst = approved_count.to_le_bytes();
+        *withdrawn_count_dst = withdrawn_count.to_le_bytes();
+        *mint_count_dst = mint_count.to_le_bytes();
+        *vesting_cliff_dst = vesting_cliff.to_le_bytes();
+        *vesting_duration_dst = vesting_duration.to_le_bytes();
+        *escrowed_dst = escrowed.to_le_bytes();
+        *settled_count_dst = settled_count.to_le_bytes();
+        *capped_count_dst = capped_count.to_le_bytes();
+        capped_area.to_little_endian(capped_area_dst);
+        last_settled_area.to_little_endian(last_settled_area_dst);
+        *last_settled_index_dst = last_settled_index.to_le_bytes();
+        *donated_dst = donated.to_le_bytes();
+        *finished_time_dst = finished_time.to_le_bytes();
     }
 }
 
@@ -85,6 +431,45 @@ pub struct Project {
     pub votes: u64,
     pub area: U256,
     pub area_sqrt: U256,
+    pub matched: u64,
+    pub index: u64,
+    pub voter_count: u64,
+    pub pairwise_area: U256,
+    pub status: ProjectStatus,
+    /// Wallet which paid the rent of the project account, it gets the rent
+    /// back on `CloseProject`
+    pub payer: Pubkey,
+    /// Part of the payout held in the vesting escrow of the project
+    pub escrowed: u64,
+    /// Part of the escrow claimed by the project so far
+    pub claimed: u64,
+    /// Set when the round owner claws back the unvested escrow, what vested
+    /// until then stays claimable
+    pub flagged: bool,
+}
+impl Project {
+    /// Length of a project account holding `metadata` after the project
+    pub fn account_len(metadata: &ProjectMetadata) -> usize {
+        Self::LEN + metadata.packed_len()
+    }
+
+    /// Unpacks the project at the start of a project account
+    pub fn unpack_account(src: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack(
+            src.get(..Self::LEN)
+                .ok_or(ProgramError::InvalidAccountData)?,
+        )
+    }
+
+    /// Packs the project at the start of a project account, leaving its
+    /// metadata as is
+    pub fn pack_account(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        Self::pack(
+            src,
+            dst.get_mut(..Self::LEN)
+                .ok_or(ProgramError::InvalidAccountData)?,
+        )
+    }
 }
 impl Sealed for Project {}
 impl IsInitialized for Project {
@@ -93,11 +478,26 @@ impl IsInitialized for Project {
     }
 }
 impl Pack for Project {
-    const LEN: usize = 137;
+    const LEN: usize = 243;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, 137];
-        let (round, owner, withdraw, votes, area, area_sqrt) =
-            array_refs![src, 32, 32, 1, 8, 32, 32];
+        let src = array_ref![src, 0, 243];
+        let (
+            round,
+            owner,
+            withdraw,
+            votes,
+            area,
+            area_sqrt,
+            matched,
+            index,
+            voter_count,
+            pairwise_area,
+            status,
+            payer,
+            escrowed,
+            claimed,
+            flagged,
+        ) = array_refs![src, 32, 32, 1, 8, 32, 32, 8, 8, 8, 32, 1, 32, 8, 8, 1];
         Ok(Project {
             round: Pubkey::new_from_array(*round),
             owner: Pubkey::new_from_array(*owner),
@@ -109,12 +509,41 @@ impl Pack for Project {
             votes: u64::from_le_bytes(*votes),
             area: U256::from_little_endian(area),
             area_sqrt: U256::from_little_endian(area_sqrt),
+            matched: u64::from_le_bytes(*matched),
+            index: u64::from_le_bytes(*index),
+            voter_count: u64::from_le_bytes(*voter_count),
+            pairwise_area: U256::from_little_endian(pairwise_area),
+            status: ProjectStatus::try_from_primitive(status[0])
+                .or(Err(ProgramError::InvalidAccountData))?,
+            payer: Pubkey::new_from_array(*payer),
+            escrowed: u64::from_le_bytes(*escrowed),
+            claimed: u64::from_le_bytes(*claimed),
+            flagged: match flagged {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
         })
     }
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, 137];
-        let (round_dst, owner_dst, withdraw_dst, votes_dst, area_dst, area_sqrt_dst) =
-            mut_array_refs![dst, 32, 32, 1, 8, 32, 32];
+        let dst = array_mut_ref![dst, 0, 243];
+        let (
+            round_dst,
+            owner_dst,
+            withdraw_dst,
+            votes_dst,
+            area_dst,
+            area_sqrt_dst,
+            matched_dst,
+            index_dst,
+            voter_count_dst,
+            pairwise_area_dst,
+            status_dst,
+            payer_dst,
+            escrowed_dst,
+            claimed_dst,
+            flagged_dst,
+        ) = mut_array_refs![dst, 32, 32, 1, 8, 32, 32, 8, 8, 8, 32, 1, 32, 8, 8, 1];
         let &Project {
             ref round,
             ref owner,
@@ -122,6 +551,15 @@ impl Pack for Project {
             votes,
             area,
             area_sqrt,
+            matched,
+            index,
+            voter_count,
+            pairwise_area,
+            status,
+            ref payer,
+            escrowed,
+            claimed,
+            flagged,
         } = self;
         round_dst.copy_from_slice(round.as_ref());
         owner_dst.copy_from_slice(owner.as_ref());
@@ -129,16 +567,102 @@ impl Pack for Project {
         *votes_dst = votes.to_le_bytes();
         area.to_little_endian(area_dst);
         area_sqrt.to_little_endian(area_sqrt_dst);
+        *matched_dst = matched.to_le_bytes();
+        *index_dst = index.to_le_bytes();
+        *voter_count_dst = voter_count.to_le_bytes();
+        pairwise_area.to_little_endian(pairwise_area_dst);
+        status_dst[0] = status as u8;
+        payer_dst.copy_from_slice(payer.as_ref());
+        *escrowed_dst = escrowed.to_le_bytes();
+        *claimed_dst = claimed.to_le_bytes();
+        flagged_dst[0] = flagged as u8;
     }
 }
 
-/// Voter
+/// Metadata of a project, stored after the project in its account. Strings are
+/// packed with a u16 length prefix. A default `payout` lets the owner withdraw
+/// to any token account of the round mint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProjectMetadata {
+    pub name: String,
+    pub description_uri: String,
+    pub image_uri: String,
+    pub payout: Pubkey,
+}
+impl ProjectMetadata {
+    /// Length of the largest metadata a project can hold
+    pub const MAX_LEN: usize = 2 + MAX_PROJECT_NAME_LEN + (2 + MAX_PROJECT_URI_LEN) * 2 + 32;
+
+    pub fn packed_len(&self) -> usize {
+        2 + self.name.len() + 2 + self.description_uri.len() + 2 + self.image_uri.len() + 32
+    }
+
+    /// Checks the lengths of the strings against their maximum
+    pub fn is_valid(&self) -> bool {
+        self.name.len() <= MAX_PROJECT_NAME_LEN
+            && self.description_uri.len() <= MAX_PROJECT_URI_LEN
+            && self.image_uri.len() <= MAX_PROJECT_URI_LEN
+    }
+
+    pub fn unpack(src: &[u8]) -> Result<Self, ProgramError> {
+        let (name, rest) = Self::unpack_string(src)?;
+        let (description_uri, rest) = Self::unpack_string(rest)?;
+        let (image_uri, rest) = Self::unpack_string(rest)?;
+        let payout = rest
+            .get(..32)
+            .and_then(|payout| payout.try_into().ok())
+            .map(Pubkey::new_from_array)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Ok(ProjectMetadata {
+            name,
+            description_uri,
+            image_uri,
+            payout,
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.packed_len());
+        for string in [&self.name, &self.description_uri, &self.image_uri] {
+            buf.extend_from_slice(&(string.len() as u16).to_le_bytes());
+            buf.extend_from_slice(string.as_bytes());
+        }
+        buf.extend_from_slice(self.payout.as_ref());
+        buf
+    }
+
+    fn unpack_string(src: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+        if src.len() < 2 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (len, rest) = src.split_at(2);
+        let len = u16::from_le_bytes([len[0], len[1]]) as usize;
+        if rest.len() < len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (string, rest) = rest.split_at(len);
+        let string =
+            String::from_utf8(string.to_vec()).or(Err(ProgramError::InvalidAccountData))?;
+        Ok((string, rest))
+    }
+}
+
+/// Voter, the votes of a wallet on a project. All votes of a voter are paid in
+/// the same mint, set by its first vote. `votes_sqrt` is the square root of
+/// the votes, every vote scaled by the share of it which is matched when it
+/// was cast. `weight_bps` is that share for the latest vote.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Voter {
     pub is_initialized: bool,
     pub votes: u64,
     pub votes_sqrt: U256,
+    pub amount: u64,
+    pub mint: Pubkey,
+    pub weight_bps: u16,
+    /// Wallet which paid the rent of the voter account, it gets the rent back
+    /// on `CloseVoter`
+    pub payer: Pubkey,
 }
 impl Sealed for Voter {}
 impl IsInitialized for Voter {
@@ -147,10 +671,11 @@ impl IsInitialized for Voter {
     }
 }
 impl Pack for Voter {
-    const LEN: usize = 41;
+    const LEN: usize = 115;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, 41];
-        let (is_initialized, votes, votes_sqrt) = array_refs![src, 1, 8, 32];
+        let src = array_ref![src, 0, 115];
+        let (is_initialized, votes, votes_sqrt, amount, mint, weight_bps, payer) =
+            array_refs![src, 1, 8, 32, 8, 32, 2, 32];
         Ok(Voter {
             is_initialized: match is_initialized {
                 [0] => false,
@@ -159,19 +684,478 @@ impl Pack for Voter {
             },
             votes: u64::from_le_bytes(*votes),
             votes_sqrt: U256::from_little_endian(votes_sqrt),
+            amount: u64::from_le_bytes(*amount),
+            mint: Pubkey::new_from_array(*mint),
+            weight_bps: u16::from_le_bytes(*weight_bps),
+            payer: Pubkey::new_from_array(*payer),
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, 41];
-        let (is_initialized_dst, votes_dst, votes_sqrt_dst) = mut_array_refs![dst, 1, 8, 32];
+        let dst = array_mut_ref![dst, 0, 115];
+        let (
+            is_initialized_dst,
+            votes_dst,
+            votes_sqrt_dst,
+            amount_dst,
+            mint_dst,
+            weight_bps_dst,
+            payer_dst,
+        ) = mut_array_refs![dst, 1, 8, 32, 8, 32, 2, 32];
         let &Voter {
             is_initialized,
             votes,
             votes_sqrt,
+            amount,
+            ref mint,
+            weight_bps,
+            ref payer,
         } = self;
         is_initialized_dst[0] = is_initialized as u8;
         *votes_dst = votes.to_le_bytes();
         votes_sqrt.to_little_endian(votes_sqrt_dst);
+        *amount_dst = amount.to_le_bytes();
+        mint_dst.copy_from_slice(mint.as_ref());
+        *weight_bps_dst = weight_bps.to_le_bytes();
+        payer_dst.copy_from_slice(payer.as_ref());
+    }
+}
+
+/// Donor, the total a wallet donated to a round in one mint
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Donor {
+    pub is_initialized: bool,
+    pub round: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+    pub value: u64,
+}
+impl Sealed for Donor {}
+impl IsInitialized for Donor {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for Donor {
+    const LEN: usize = 113;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 113];
+        let (is_initialized, round, authority, amount, mint, value) =
+            array_refs![src, 1, 32, 32, 8, 32, 8];
+        Ok(Donor {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            round: Pubkey::new_from_array(*round),
+            authority: Pubkey::new_from_array(*authority),
+            amount: u64::from_le_bytes(*amount),
+            mint: Pubkey::new_from_array(*mint),
+            value: u64::from_le_bytes(*value),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 113];
+        let (is_initialized_dst, round_dst, authority_dst, amount_dst, mint_dst, value_dst) =
+            mut_array_refs![dst, 1, 32, 32, 8, 32, 8];
+        let &Donor {
+            is_initialized,
+            ref round,
+            ref authority,
+            amount,
+            ref mint,
+            value,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        round_dst.copy_from_slice(round.as_ref());
+        authority_dst.copy_from_slice(authority.as_ref());
+        *amount_dst = amount.to_le_bytes();
+        mint_dst.copy_from_slice(mint.as_ref());
+        *value_dst = value.to_le_bytes();
+    }
+}
+
+/// Voter pair, the overlap of two voters in a pairwise round. The account data
+/// is followed by two bitmaps indexed by `Project.index`, the projects already
+/// accumulated into `overlap` and the projects already distributed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VoterPair {
+    pub is_initialized: bool,
+    pub round: Pubkey,
+    pub voter_a: Pubkey,
+    pub voter_b: Pubkey,
+    pub overlap: U256,
+}
+impl VoterPair {
+    /// Length of one project bitmap of a round with `project_count` projects
+    pub fn bitmap_len(project_count: u64) -> usize {
+        project_count.div_ceil(8) as usize
+    }
+
+    /// Length of a voter pair account of a round with `project_count` projects
+    pub fn account_len(project_count: u64) -> usize {
+        Self::LEN + Self::bitmap_len(project_count) * 2
+    }
+}
+impl Sealed for VoterPair {}
+impl IsInitialized for VoterPair {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for VoterPair {
+    const LEN: usize = 129;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 129];
+        let (is_initialized, round, voter_a, voter_b, overlap) =
+            array_refs![src, 1, 32, 32, 32, 32];
+        Ok(VoterPair {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            round: Pubkey::new_from_array(*round),
+            voter_a: Pubkey::new_from_array(*voter_a),
+            voter_b: Pubkey::new_from_array(*voter_b),
+            overlap: U256::from_little_endian(overlap),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 129];
+        let (is_initialized_dst, round_dst, voter_a_dst, voter_b_dst, overlap_dst) =
+            mut_array_refs![dst, 1, 32, 32, 32, 32];
+        let &VoterPair {
+            is_initialized,
+            ref round,
+            ref voter_a,
+            ref voter_b,
+            overlap,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        round_dst.copy_from_slice(round.as_ref());
+        voter_a_dst.copy_from_slice(voter_a.as_ref());
+        voter_b_dst.copy_from_slice(voter_b.as_ref());
+        overlap.to_little_endian(overlap_dst);
+    }
+}
+
+/// Price feed, what one unit of a mint is worth in units of a round mint,
+/// scaled by `PRICE_SCALE` and set by its authority
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PriceFeed {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub price: u64,
+}
+impl Sealed for PriceFeed {}
+impl IsInitialized for PriceFeed {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for PriceFeed {
+    const LEN: usize = 41;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 41];
+        let (is_initialized, authority, price) = array_refs![src, 1, 32, 8];
+        Ok(PriceFeed {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            authority: Pubkey::new_from_array(*authority),
+            price: u64::from_le_bytes(*price),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 41];
+        let (is_initialized_dst, authority_dst, price_dst) = mut_array_refs![dst, 1, 32, 8];
+        let &PriceFeed {
+            is_initialized,
+            ref authority,
+            price,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        authority_dst.copy_from_slice(authority.as_ref());
+        *price_dst = price.to_le_bytes();
+    }
+}
+
+/// Round mint, another mint a round accepts besides its own. Donations and
+/// votes in it are converted into the round mint with the price feed and it
+/// is paid out in proportion to what was deposited in its vault.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RoundMint {
+    pub is_initialized: bool,
+    pub round: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub price_feed: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub deposited: u64,
+    pub fee_withdrawn: u64,
+    /// Donations in the vault which are not refunded
+    pub donated: u64,
+    /// Number of projects which withdrew their share from the vault
+    pub withdrawn_count: u64,
+}
+impl Sealed for RoundMint {}
+impl IsInitialized for RoundMint {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for RoundMint {
+    const LEN: usize = 193;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 193];
+        let (
+            is_initialized,
+            round,
+            mint,
+            vault,
+            price_feed,
+            fee_recipient,
+            deposited,
+            fee_withdrawn,
+            donated,
+            withdrawn_count,
+        ) = array_refs![src, 1, 32, 32, 32, 32, 32, 8, 8, 8, 8];
+        Ok(RoundMint {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            round: Pubkey::new_from_array(*round),
+            mint: Pubkey::new_from_array(*mint),
+            vault: Pubkey::new_from_array(*vault),
+            price_feed: Pubkey::new_from_array(*price_feed),
+            fee_recipient: Pubkey::new_from_array(*fee_recipient),
+            deposited: u64::from_le_bytes(*deposited),
+            fee_withdrawn: u64::from_le_bytes(*fee_withdrawn),
+            donated: u64::from_le_bytes(*donated),
+            withdrawn_count: u64::from_le_bytes(*withdrawn_count),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 193];
+        let (
+            is_initialized_dst,
+            round_dst,
+            mint_dst,
+            vault_dst,
+            price_feed_dst,
+            fee_recipient_dst,
+            deposited_dst,
+            fee_withdrawn_dst,
+            donated_dst,
+            withdrawn_count_dst,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 32, 32, 8, 8, 8, 8];
+        let &RoundMint {
+            is_initialized,
+            ref round,
+            ref mint,
+            ref vault,
+            ref price_feed,
+            ref fee_recipient,
+            deposited,
+            fee_withdrawn,
+            donated,
+            withdrawn_count,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        round_dst.copy_from_slice(round.as_ref());
+        mint_dst.copy_from_slice(mint.as_ref());
+        vault_dst.copy_from_slice(vault.as_ref());
+        price_feed_dst.copy_from_slice(price_feed.as_ref());
+        fee_recipient_dst.copy_from_slice(fee_recipient.as_ref());
+        *deposited_dst = deposited.to_le_bytes();
+        *fee_withdrawn_dst = fee_withdrawn.to_le_bytes();
+        *donated_dst = donated.to_le_bytes();
+        *withdrawn_count_dst = withdrawn_count.to_le_bytes();
+    }
+}
+
+/// Attestation of an identity issuer that a wallet belongs to a unique person,
+/// derived from `"attestation"`, the issuer and the wallet. `trust_bps` is the
+/// share of the votes of the wallet which is matched in rounds gated by the
+/// issuer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Attestation {
+    pub is_initialized: bool,
+    pub issuer: Pubkey,
+    pub wallet: Pubkey,
+    pub trust_bps: u16,
+}
+impl Sealed for Attestation {}
+impl IsInitialized for Attestation {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for Attestation {
+    const LEN: usize = 67;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 67];
+        let (is_initialized, issuer, wallet, trust_bps) = array_refs![src, 1, 32, 32, 2];
+        Ok(Attestation {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            issuer: Pubkey::new_from_array(*issuer),
+            wallet: Pubkey::new_from_array(*wallet),
+            trust_bps: u16::from_le_bytes(*trust_bps),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 67];
+        let (is_initialized_dst, issuer_dst, wallet_dst, trust_bps_dst) =
+            mut_array_refs![dst, 1, 32, 32, 2];
+        let &Attestation {
+            is_initialized,
+            ref issuer,
+            ref wallet,
+            trust_bps,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        issuer_dst.copy_from_slice(issuer.as_ref());
+        wallet_dst.copy_from_slice(wallet.as_ref());
+        *trust_bps_dst = trust_bps.to_le_bytes();
+    }
+}
+
+/// Curator of a round, a wallet approving and rejecting projects besides the
+/// round owner, derived from `"curator"`, the round and the wallet
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Curator {
+    pub is_initialized: bool,
+    pub round: Pubkey,
+    pub wallet: Pubkey,
+}
+impl Sealed for Curator {}
+impl IsInitialized for Curator {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for Curator {
+    const LEN: usize = 65;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 65];
+        let (is_initialized, round, wallet) = array_refs![src, 1, 32, 32];
+        Ok(Curator {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            round: Pubkey::new_from_array(*round),
+            wallet: Pubkey::new_from_array(*wallet),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 65];
+        let (is_initialized_dst, round_dst, wallet_dst) = mut_array_refs![dst, 1, 32, 32];
+        let &Curator {
+            is_initialized,
+            ref round,
+            ref wallet,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        round_dst.copy_from_slice(round.as_ref());
+        wallet_dst.copy_from_slice(wallet.as_ref());
+    }
+}
+
+/// Program wide config, derived from `"config"`. Once it exists only the
+/// wallets allowed by its admin can start rounds.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Config {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+}
+impl Sealed for Config {}
+impl IsInitialized for Config {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for Config {
+    const LEN: usize = 33;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 33];
+        let (is_initialized, admin) = array_refs![src, 1, 32];
+        Ok(Config {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            admin: Pubkey::new_from_array(*admin),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 33];
+        let (is_initialized_dst, admin_dst) = mut_array_refs![dst, 1, 32];
+        let &Config {
+            is_initialized,
+            ref admin,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        admin_dst.copy_from_slice(admin.as_ref());
+    }
+}
+
+/// Round creator, a wallet allowed to start rounds, derived from `"creator"`
+/// and the wallet
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Creator {
+    pub is_initialized: bool,
+    pub wallet: Pubkey,
+}
+impl Sealed for Creator {}
+impl IsInitialized for Creator {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for Creator {
+    const LEN: usize = 33;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 33];
+        let (is_initialized, wallet) = array_refs![src, 1, 32];
+        Ok(Creator {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            wallet: Pubkey::new_from_array(*wallet),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 33];
+        let (is_initialized_dst, wallet_dst) = mut_array_refs![dst, 1, 32];
+        let &Creator {
+            is_initialized,
+            ref wallet,
+        } = self;
+        is_initialized_dst[0] = is_initialized as u8;
+        wallet_dst.copy_from_slice(wallet.as_ref());
     }
 }
@@ -0,0 +1,3086 @@
+use qf::{
+    error::QFError,
+    instruction::{self, RoundConfig},
+    payout,
+    processor::Processor,
+    state::{
+        EmptyRoundPolicy, FeePolicy, MatchingAlgorithm, PriceFeed, Project, ProjectMetadata,
+        ProjectStatus, Round, RoundMint, RoundStatus, Voter, MAX_PROJECT_NAME_LEN, PRICE_SCALE,
+    },
+};
+use solana_program::{
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::clock::Clock,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::AccountSharedData,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_math::{precise_number::ONE, uint::U256};
+use std::slice;
+
+const DECIMALS: u8 = 6;
+
+struct TestRound {
+    round: Pubkey,
+    owner: Keypair,
+    fee_recipient: Pubkey,
+}
+
+struct TestProject {
+    project: Pubkey,
+    keypair: Keypair,
+    owner: Keypair,
+}
+
+struct TestVoter {
+    wallet: Keypair,
+    token_account: Pubkey,
+}
+
+struct Env {
+    ctx: ProgramTestContext,
+    program_id: Pubkey,
+    mint: Pubkey,
+    now: i64,
+}
+
+impl Env {
+    async fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let mut ctx = ProgramTest::new("qf", program_id, processor!(Processor::process))
+            .start_with_context()
+            .await;
+        let now = ctx
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp;
+
+        let mut env = Self {
+            ctx,
+            program_id,
+            mint: Pubkey::default(),
+            now,
+        };
+        env.mint = env.create_mint().await;
+        env
+    }
+
+    /// Creates a mint with the payer as mint authority
+    async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let payer = self.ctx.payer.pubkey();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    DECIMALS,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        mint.pubkey()
+    }
+
+    fn config(&self) -> RoundConfig {
+        RoundConfig {
+            start_time: self.now - 1,
+            end_time: self.now + 1_000,
+            registration_end_time: self.now + 1_000,
+            fee_bps: 0,
+            fee_policy: FeePolicy::Total,
+            matching_cap_bps: 0,
+            matching_algorithm: MatchingAlgorithm::Quadratic,
+            pairwise_threshold: 0,
+            identity_issuer: Pubkey::default(),
+            curated: false,
+            empty_round_policy: EmptyRoundPolicy::RefundDonors,
+            vesting_cliff: 0,
+            vesting_duration: 0,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let mut all_signers = vec![&self.ctx.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.ctx.payer.pubkey()),
+            &all_signers,
+            self.ctx.last_blockhash,
+        );
+        self.ctx.banks_client.process_transaction(transaction).await
+    }
+
+    /// Lets an identical transaction be sent again
+    async fn refresh_blockhash(&mut self) {
+        self.ctx.get_new_latest_blockhash().await.unwrap();
+    }
+
+    /// Stores the program data account of an upgradeable program with
+    /// `authority` as its upgrade authority
+    fn set_upgrade_authority(&mut self, authority: &Pubkey) {
+        let program_data = AccountSharedData::new_data(
+            1_000_000_000,
+            &UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(*authority),
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        let address = instruction::get_program_data_address(&self.program_id);
+        self.ctx.set_account(&address, &program_data);
+    }
+
+    async fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock = self.ctx.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    async fn create_token_account(&mut self, owner: &Pubkey, amount: u64) -> Pubkey {
+        let mint = self.mint;
+        self.create_token_account_of(&mint, owner, amount).await
+    }
+
+    async fn create_token_account_of(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let payer = self.ctx.payer.pubkey();
+        let mut instructions = vec![
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap(),
+        ];
+        // the native mint has no mint authority
+        if amount > 0 {
+            instructions.push(
+                spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    mint,
+                    &account.pubkey(),
+                    &payer,
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            );
+        }
+        self.process(&instructions, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    /// Creates an SPL token multisig account of `signers` which takes `m` of
+    /// them to sign
+    async fn create_multisig(&mut self, signers: &[&Pubkey], m: u8) -> Pubkey {
+        let multisig = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let payer = self.ctx.payer.pubkey();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &multisig.pubkey(),
+                    rent.minimum_balance(spl_token::state::Multisig::LEN),
+                    spl_token::state::Multisig::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_multisig(
+                    &spl_token::id(),
+                    &multisig.pubkey(),
+                    signers,
+                    m,
+                )
+                .unwrap(),
+            ],
+            &[&multisig],
+        )
+        .await
+        .unwrap();
+        multisig.pubkey()
+    }
+
+    /// Instructions creating the round account and starting the round, the
+    /// returned keypair is the round account which has to sign
+    async fn start_round_instructions(
+        &mut self,
+        config: RoundConfig,
+    ) -> (Keypair, TestRound, Vec<Instruction>) {
+        let round = Keypair::new();
+        let owner = Keypair::new();
+        let fee_recipient = self.create_token_account(&owner.pubkey(), 0).await;
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let payer = self.ctx.payer.pubkey();
+        let instructions = vec![
+            system_instruction::create_account(
+                &payer,
+                &round.pubkey(),
+                rent.minimum_balance(Round::LEN),
+                Round::LEN as u64,
+                &self.program_id,
+            ),
+            instruction::start_round(
+                &self.program_id,
+                &round.pubkey(),
+                &owner.pubkey(),
+                &payer,
+                &self.mint,
+                &fee_recipient,
+                config,
+            ),
+        ];
+        let test_round = TestRound {
+            round: round.pubkey(),
+            owner,
+            fee_recipient,
+        };
+        (round, test_round, instructions)
+    }
+
+    async fn start_round(&mut self, config: RoundConfig) -> TestRound {
+        let (round, test_round, instructions) = self.start_round_instructions(config).await;
+        self.process(&instructions, &[&round, &test_round.owner])
+            .await
+            .unwrap();
+        test_round
+    }
+
+    async fn try_register_project(
+        &mut self,
+        round: &TestRound,
+    ) -> (TestProject, Result<(), BanksClientError>) {
+        self.try_register_project_with(round, &ProjectMetadata::default())
+            .await
+    }
+
+    async fn try_register_project_with(
+        &mut self,
+        round: &TestRound,
+        metadata: &ProjectMetadata,
+    ) -> (TestProject, Result<(), BanksClientError>) {
+        let project = Keypair::new();
+        let owner = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let payer = self.ctx.payer.pubkey();
+        let result = self
+            .process(
+                &[
+                    system_instruction::create_account(
+                        &payer,
+                        &project.pubkey(),
+                        rent.minimum_balance(Project::account_len(metadata)),
+                        Project::account_len(metadata) as u64,
+                        &self.program_id,
+                    ),
+                    instruction::register_project(
+                        &self.program_id,
+                        &project.pubkey(),
+                        &round.round,
+                        &owner.pubkey(),
+                        &payer,
+                        metadata,
+                    ),
+                ],
+                &[&project],
+            )
+            .await;
+        let test_project = TestProject {
+            project: project.pubkey(),
+            keypair: project,
+            owner,
+        };
+        (test_project, result)
+    }
+
+    async fn register_project(&mut self, round: &TestRound) -> TestProject {
+        let (project, result) = self.try_register_project(round).await;
+        result.unwrap();
+        project
+    }
+
+    /// Donates from a new token account of the payer and returns the token account
+    async fn donate(&mut self, round: &TestRound, amount: u64) -> Pubkey {
+        let payer = self.ctx.payer.pubkey();
+        let from = self.create_token_account(&payer, amount).await;
+        let donate = instruction::donate(
+            &self.program_id,
+            &round.round,
+            &from,
+            &self.mint,
+            &payer,
+            amount,
+            DECIMALS,
+        );
+        self.process(&[donate], &[]).await.unwrap();
+        from
+    }
+
+    /// Creates a price feed and accepts its mint in the round, returns the
+    /// mint and the price feed
+    async fn add_mint(&mut self, round: &TestRound, price: u64) -> (Pubkey, Pubkey) {
+        let mint = self.create_mint().await;
+        let price_feed = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let payer = self.ctx.payer.pubkey();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &price_feed.pubkey(),
+                    rent.minimum_balance(PriceFeed::LEN),
+                    PriceFeed::LEN as u64,
+                    &self.program_id,
+                ),
+                instruction::init_price_feed(&self.program_id, &price_feed.pubkey(), &payer, price),
+            ],
+            &[&price_feed],
+        )
+        .await
+        .unwrap();
+
+        let fee_recipient = self
+            .create_token_account_of(&mint, &round.owner.pubkey(), 0)
+            .await;
+        let add_mint = instruction::add_mint(
+            &self.program_id,
+            &round.round,
+            &round.owner.pubkey(),
+            &mint,
+            &price_feed.pubkey(),
+            &fee_recipient,
+            &payer,
+        );
+        self.process(&[add_mint], &[&round.owner]).await.unwrap();
+        (mint, price_feed.pubkey())
+    }
+
+    /// Creates a voter with `balance` tokens and its voter account on the project
+    async fn create_voter(&mut self, project: &TestProject, balance: u64) -> TestVoter {
+        let mint = self.mint;
+        self.create_voter_of(&mint, project, balance).await
+    }
+
+    async fn create_voter_of(
+        &mut self,
+        mint: &Pubkey,
+        project: &TestProject,
+        balance: u64,
+    ) -> TestVoter {
+        let wallet = Keypair::new();
+        let token_account = self
+            .create_token_account_of(mint, &wallet.pubkey(), balance)
+            .await;
+        let init_voter = instruction::init_voter(
+            &self.program_id,
+            &project.project,
+            &wallet.pubkey(),
+            &self.ctx.payer.pubkey(),
+        );
+        self.process(&[init_voter], &[&wallet]).await.unwrap();
+        TestVoter {
+            wallet,
+            token_account,
+        }
+    }
+
+    async fn attest(
+        &mut self,
+        issuer: &Keypair,
+        wallet: &Pubkey,
+        trust_bps: u16,
+    ) -> Result<(), BanksClientError> {
+        let attest = instruction::attest(
+            &self.program_id,
+            &issuer.pubkey(),
+            wallet,
+            &self.ctx.payer.pubkey(),
+            trust_bps,
+        );
+        self.process(&[attest], &[issuer]).await
+    }
+
+    /// Votes in a round gated by `issuer` with the attestation of the voter
+    async fn vote_attested(
+        &mut self,
+        round: &TestRound,
+        project: &TestProject,
+        voter: &TestVoter,
+        issuer: &Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let vote = instruction::with_attestation(
+            self.vote_instruction(round, project, voter, amount),
+            &self.program_id,
+            issuer,
+            &voter.wallet.pubkey(),
+        );
+        self.process(&[vote], &[&voter.wallet]).await
+    }
+
+    fn vote_instruction(
+        &self,
+        round: &TestRound,
+        project: &TestProject,
+        voter: &TestVoter,
+        amount: u64,
+    ) -> Instruction {
+        instruction::vote(
+            &self.program_id,
+            &round.round,
+            &project.project,
+            &voter.token_account,
+            &self.mint,
+            &voter.wallet.pubkey(),
+            amount,
+            DECIMALS,
+        )
+    }
+
+    async fn vote(
+        &mut self,
+        round: &TestRound,
+        project: &TestProject,
+        voter: &TestVoter,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let vote = self.vote_instruction(round, project, voter, amount);
+        self.process(&[vote], &[&voter.wallet]).await
+    }
+
+    async fn end_round(&mut self, round: &TestRound) {
+        let end_round =
+            instruction::end_round(&self.program_id, &round.round, &round.owner.pubkey());
+        self.process(&[end_round], &[&round.owner]).await.unwrap();
+    }
+
+    /// Withdraws the project to a new token account of its owner and returns it
+    async fn withdraw(
+        &mut self,
+        round: &TestRound,
+        project: &TestProject,
+    ) -> (Pubkey, Result<(), BanksClientError>) {
+        let to = self.create_token_account(&project.owner.pubkey(), 0).await;
+        let withdraw = instruction::withdraw(
+            &self.program_id,
+            &round.round,
+            &self.mint,
+            &project.project,
+            &project.owner.pubkey(),
+            &to,
+        );
+        let result = self.process(&[withdraw], &[&project.owner]).await;
+        (to, result)
+    }
+
+    async fn get_round(&mut self, round: &TestRound) -> Round {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(round.round)
+            .await
+            .unwrap();
+        Round::unpack(&account.unwrap().data).unwrap()
+    }
+
+    async fn get_project(&mut self, project: &TestProject) -> Project {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(project.project)
+            .await
+            .unwrap();
+        Project::unpack_account(&account.unwrap().data).unwrap()
+    }
+
+    async fn get_project_metadata(&mut self, project: &TestProject) -> (usize, ProjectMetadata) {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(project.project)
+            .await
+            .unwrap()
+            .unwrap();
+        let metadata = ProjectMetadata::unpack(&account.data[Project::LEN..]).unwrap();
+        (account.data.len(), metadata)
+    }
+
+    async fn get_voter(&mut self, project: &TestProject, wallet: &Pubkey) -> Voter {
+        let address = instruction::get_voter_address(&self.program_id, &project.project, wallet);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap();
+        Voter::unpack(&account.unwrap().data).unwrap()
+    }
+
+    async fn get_round_mint(&mut self, round: &TestRound, mint: &Pubkey) -> RoundMint {
+        let address = instruction::get_round_mint_address(&self.program_id, &round.round, mint);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap();
+        RoundMint::unpack(&account.unwrap().data).unwrap()
+    }
+
+    async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap();
+        spl_token::state::Account::unpack(&account.unwrap().data)
+            .unwrap()
+            .amount
+    }
+
+    fn vault(&self, round: &TestRound) -> Pubkey {
+        instruction::get_vault_address(&self.program_id, &round.round, &self.mint)
+    }
+}
+
+fn qf_error(index: u8, error: QFError) -> TransactionError {
+    TransactionError::InstructionError(index, InstructionError::Custom(error as u32))
+}
+
+fn assert_qf_error(result: Result<(), BanksClientError>, index: u8, error: QFError) {
+    assert_eq!(result.unwrap_err().unwrap(), qf_error(index, error));
+}
+
+#[tokio::test]
+async fn test_round_lifecycle() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            fee_bps: 500,
+            ..env.config()
+        })
+        .await;
+    env.donate(&round, 1_000).await;
+
+    let project_a = env.register_project(&round).await;
+    let project_b = env.register_project(&round).await;
+    for (project, amount) in [(&project_a, 100), (&project_a, 100), (&project_b, 400)] {
+        let voter = env.create_voter(project, amount).await;
+        env.vote(&round, project, &voter, amount).await.unwrap();
+    }
+
+    let (_, result) = env.withdraw(&round, &project_a).await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+
+    env.end_round(&round).await;
+    let state = env.get_round(&round).await;
+    assert_eq!(state.status, RoundStatus::Finished);
+    assert_eq!(state.mint, env.mint);
+    assert_eq!(state.fund, 1_000);
+    assert_eq!(state.project_count, 2);
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 1_600);
+
+    let projects = vec![
+        env.get_project(&project_a).await,
+        env.get_project(&project_b).await,
+    ];
+    assert_eq!(projects[0].votes, 200);
+    assert_eq!(projects[1].votes, 400);
+    let payouts = payout::simulate_payouts(&state, &projects).unwrap();
+
+    let other_mint = env.create_mint().await;
+    let to = env
+        .create_token_account_of(&other_mint, &project_a.owner.pubkey(), 0)
+        .await;
+    let withdraw = instruction::withdraw(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &project_a.project,
+        &project_a.owner.pubkey(),
+        &to,
+    );
+    let result = env.process(&[withdraw], &[&project_a.owner]).await;
+    assert_qf_error(result, 0, QFError::MintMismatch);
+
+    let (to_a, result) = env.withdraw(&round, &project_a).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to_a).await, payouts[0].amount);
+    let (to_b, result) = env.withdraw(&round, &project_b).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to_b).await, payouts[1].amount);
+
+    let (_, result) = env.withdraw(&round, &project_a).await;
+    assert_qf_error(result, 0, QFError::ProjectAlreadyWithdraw);
+
+    let state = env.get_round(&round).await;
+    assert_eq!(state.fee, payouts[0].fee + payouts[1].fee);
+    assert_eq!(state.paid_out, payouts[0].amount + payouts[1].amount);
+
+    let cancel_round =
+        instruction::cancel_round(&env.program_id, &round.round, &round.owner.pubkey());
+    let result = env.process(&[cancel_round], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::RoundAlreadyPaidOut);
+
+    let other = env.create_token_account(&round.owner.pubkey(), 0).await;
+    let withdraw_fee = instruction::withdraw_fee(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &env.mint,
+        &other,
+    );
+    let result = env.process(&[withdraw_fee], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::FeeRecipientMismatch);
+
+    let withdraw_fee = instruction::withdraw_fee(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &env.mint,
+        &round.fee_recipient,
+    );
+    env.process(&[withdraw_fee], &[&round.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&round.fee_recipient).await, state.fee);
+    assert_eq!(env.get_round(&round).await.fee, 0);
+    assert_eq!(
+        env.token_balance(&env.vault(&round)).await,
+        1_600 - state.paid_out - state.fee
+    );
+}
+
+#[tokio::test]
+async fn test_start_round_errors() {
+    let mut env = Env::new().await;
+    let cases = [
+        (
+            RoundConfig {
+                end_time: env.now - 1,
+                ..env.config()
+            },
+            QFError::InvalidRoundTime,
+        ),
+        (
+            RoundConfig {
+                registration_end_time: env.now + 1_001,
+                ..env.config()
+            },
+            QFError::InvalidRoundTime,
+        ),
+        (
+            RoundConfig {
+                fee_bps: 1_001,
+                ..env.config()
+            },
+            QFError::InvalidFeeRate,
+        ),
+        (
+            RoundConfig {
+                matching_cap_bps: 10_001,
+                ..env.config()
+            },
+            QFError::InvalidMatchingCap,
+        ),
+        (
+            RoundConfig {
+                matching_algorithm: MatchingAlgorithm::Pairwise,
+                pairwise_threshold: 0,
+                ..env.config()
+            },
+            QFError::InvalidPairwiseThreshold,
+        ),
+        (
+            RoundConfig {
+                vesting_cliff: 500,
+                vesting_duration: 400,
+                ..env.config()
+            },
+            QFError::InvalidVestingSchedule,
+        ),
+    ];
+    for (config, error) in cases {
+        let (round, test_round, instructions) = env.start_round_instructions(config).await;
+        let result = env
+            .process(&instructions, &[&round, &test_round.owner])
+            .await;
+        assert_qf_error(result, 1, error);
+    }
+
+    let cases = [
+        (2, QFError::UnexpectedTokenProgramID),
+        (5, QFError::OwnerMismatch),
+        (7, QFError::UnexpectedSystemProgramID),
+        (8, QFError::UnexpectedTokenProgramID),
+    ];
+    for (account, error) in cases {
+        let config = env.config();
+        let (round, test_round, mut instructions) = env.start_round_instructions(config).await;
+        instructions[1].accounts[account].pubkey = Pubkey::new_unique();
+        let result = env
+            .process(&instructions, &[&round, &test_round.owner])
+            .await;
+        assert_qf_error(result, 1, error);
+    }
+}
+
+#[tokio::test]
+async fn test_donate_errors() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    let payer = env.ctx.payer.pubkey();
+    let from = env.create_token_account(&payer, 100).await;
+
+    let cases = [
+        (3, QFError::VaultMismatch),
+        (5, QFError::UnexpectedTokenProgramID),
+        (7, QFError::DonorMismatch),
+        (8, QFError::UnexpectedSystemProgramID),
+    ];
+    for (account, error) in cases {
+        let mut donate = instruction::donate(
+            &env.program_id,
+            &round.round,
+            &from,
+            &env.mint,
+            &payer,
+            100,
+            DECIMALS,
+        );
+        donate.accounts[account].pubkey = Pubkey::new_unique();
+        let result = env.process(&[donate], &[]).await;
+        assert_qf_error(result, 0, error);
+    }
+
+    env.set_time(env.now + 1_000).await;
+    let donate = instruction::donate(
+        &env.program_id,
+        &round.round,
+        &from,
+        &env.mint,
+        &payer,
+        100,
+        DECIMALS,
+    );
+    let result = env.process(slice::from_ref(&donate), &[]).await;
+    assert_qf_error(result, 0, QFError::RoundEnded);
+
+    env.end_round(&round).await;
+    env.refresh_blockhash().await;
+    let result = env.process(&[donate], &[]).await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+}
+
+#[tokio::test]
+async fn test_round_schedule() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            start_time: env.now + 100,
+            end_time: env.now + 1_000,
+            registration_end_time: env.now + 500,
+            ..env.config()
+        })
+        .await;
+
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    let result = env.vote(&round, &project, &voter, 100).await;
+    assert_qf_error(result, 0, QFError::RoundNotStarted);
+    let payer = env.ctx.payer.pubkey();
+    let from = env.create_token_account(&payer, 100).await;
+    let donate = instruction::donate(
+        &env.program_id,
+        &round.round,
+        &from,
+        &env.mint,
+        &payer,
+        100,
+        DECIMALS,
+    );
+    let result = env.process(&[donate], &[]).await;
+    assert_qf_error(result, 0, QFError::RoundNotStarted);
+
+    env.set_time(env.now + 500).await;
+    env.donate(&round, 100).await;
+    let (_, result) = env.try_register_project(&round).await;
+    assert_qf_error(result, 1, QFError::RegistrationClosed);
+    env.vote(&round, &project, &voter, 50).await.unwrap();
+
+    let finalize_round = instruction::finalize_round(&env.program_id, &round.round);
+    let result = env.process(slice::from_ref(&finalize_round), &[]).await;
+    assert_qf_error(result, 0, QFError::RoundNotEnded);
+
+    env.set_time(env.now + 1_000).await;
+    let result = env.vote(&round, &project, &voter, 25).await;
+    assert_qf_error(result, 0, QFError::RoundEnded);
+
+    env.refresh_blockhash().await;
+    env.process(&[finalize_round], &[]).await.unwrap();
+    assert_eq!(env.get_round(&round).await.status, RoundStatus::Finished);
+}
+
+#[tokio::test]
+async fn test_account_mismatch_errors() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    let other_round = env.start_round(env.config()).await;
+    let project = env.register_project(&round).await;
+    let other_project = env.register_project(&other_round).await;
+
+    let end_round =
+        instruction::end_round(&env.program_id, &round.round, &other_round.owner.pubkey());
+    let result = env.process(&[end_round], &[&other_round.owner]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+
+    let voter = env.create_voter(&other_project, 100).await;
+    let result = env.vote(&round, &other_project, &voter, 100).await;
+    assert_qf_error(result, 0, QFError::RoundMismatch);
+
+    let voter = env.create_voter(&project, 100).await;
+    let other_voter = env.create_voter(&project, 100).await;
+    let mut vote = env.vote_instruction(&round, &project, &voter, 100);
+    vote.accounts[2].pubkey = instruction::get_voter_address(
+        &env.program_id,
+        &project.project,
+        &other_voter.wallet.pubkey(),
+    );
+    let result = env.process(&[vote], &[&voter.wallet]).await;
+    assert_qf_error(result, 0, QFError::VoterMismatch);
+
+    let mut vote = env.vote_instruction(&round, &project, &voter, 100);
+    vote.accounts[5].pubkey = env.vault(&other_round);
+    let result = env.process(&[vote], &[&voter.wallet]).await;
+    assert_qf_error(result, 0, QFError::VaultMismatch);
+
+    let mut vote = env.vote_instruction(&round, &project, &voter, 100);
+    vote.accounts[7].pubkey = Pubkey::new_unique();
+    let result = env.process(&[vote], &[&voter.wallet]).await;
+    assert_qf_error(result, 0, QFError::UnexpectedTokenProgramID);
+}
+
+#[tokio::test]
+async fn test_capped_matching() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            matching_cap_bps: 6_000,
+            ..env.config()
+        })
+        .await;
+    env.donate(&round, 1_000).await;
+
+    let project_a = env.register_project(&round).await;
+    let project_b = env.register_project(&round).await;
+    let project_c = env.register_project(&round).await;
+    for (project, amount) in [(&project_a, 900), (&project_b, 400), (&project_c, 100)] {
+        let voter = env.create_voter(project, amount).await;
+        env.vote(&round, project, &voter, amount).await.unwrap();
+    }
+    env.end_round(&round).await;
+
+    let (_, result) = env.withdraw(&round, &project_a).await;
+    assert_qf_error(result, 0, QFError::MatchingNotSettled);
+
+    let other_owner = Keypair::new();
+    let settle_matching = instruction::settle_matching(
+        &env.program_id,
+        &round.round,
+        &other_owner.pubkey(),
+        &[project_a.project],
+    );
+    let result = env.process(&[settle_matching], &[&other_owner]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+
+    // project a precedes b, so it starts the settlement over
+    let (program_id, owner) = (env.program_id, round.owner.pubkey());
+    let settle = |projects: &[&TestProject]| {
+        let projects: Vec<Pubkey> = projects.iter().map(|p| p.project).collect();
+        instruction::settle_matching(&program_id, &round.round, &owner, &projects)
+    };
+    let settle_matching = settle(&[&project_b, &project_a]);
+    env.process(&[settle_matching], &[&round.owner])
+        .await
+        .unwrap();
+    assert_eq!(env.get_round(&round).await.settled_count, 1);
+
+    // settling b twice starts over from the second one
+    let settle_matching = settle(&[&project_b, &project_b]);
+    env.process(&[settle_matching], &[&round.owner])
+        .await
+        .unwrap();
+    let state = env.get_round(&round).await;
+    assert_eq!(state.settled_count, 1);
+    assert!(!state.matching_settled);
+    let (_, result) = env.withdraw(&round, &project_a).await;
+    assert_qf_error(result, 0, QFError::MatchingNotSettled);
+
+    let settle_matching = settle(&[&project_a, &project_b]);
+    env.process(&[settle_matching], &[&round.owner])
+        .await
+        .unwrap();
+    assert!(!env.get_round(&round).await.matching_settled);
+    let settle_matching = settle(&[&project_c]);
+    env.process(slice::from_ref(&settle_matching), &[&round.owner])
+        .await
+        .unwrap();
+    env.refresh_blockhash().await;
+    let result = env.process(&[settle_matching], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::MatchingAlreadySettled);
+
+    let state = env.get_round(&round).await;
+    assert!(state.matching_settled);
+    let projects = vec![
+        env.get_project(&project_a).await,
+        env.get_project(&project_b).await,
+        env.get_project(&project_c).await,
+    ];
+    assert_eq!(payout::settlement_order(&state, &projects), vec![0, 1, 2]);
+    assert_eq!(projects[0].matched, 600);
+    assert_eq!(projects[1].matched, 320);
+    assert_eq!(projects[2].matched, 80);
+    assert_eq!(
+        payout::settled_matching(&state, &projects).unwrap(),
+        vec![600, 320, 80]
+    );
+    let payouts = payout::simulate_payouts(&state, &projects).unwrap();
+
+    for (project, payout) in [&project_a, &project_b, &project_c].iter().zip(payouts) {
+        let (to, result) = env.withdraw(&round, project).await;
+        result.unwrap();
+        assert_eq!(env.token_balance(&to).await, payout.amount);
+    }
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 0);
+}
+
+#[tokio::test]
+async fn test_multisig_settle_matching() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            matching_cap_bps: 6_000,
+            ..env.config()
+        })
+        .await;
+    env.donate(&round, 1_000).await;
+    let project_a = env.register_project(&round).await;
+    let project_b = env.register_project(&round).await;
+    for (project, amount) in [(&project_a, 900), (&project_b, 100)] {
+        let voter = env.create_voter(project, amount).await;
+        env.vote(&round, project, &voter, amount).await.unwrap();
+    }
+    env.end_round(&round).await;
+
+    // a 2 of 2 multisig owns the round
+    let signers = [Keypair::new(), Keypair::new()];
+    let signer_keys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+    let signer_refs: Vec<&Pubkey> = signer_keys.iter().collect();
+    let multisig = env.create_multisig(&signer_refs, 2).await;
+    let set_authority = instruction::set_round_authority(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &multisig,
+    );
+    let accept = instruction::with_multisig_signers(
+        instruction::accept_round_authority(&env.program_id, &round.round, &multisig),
+        &multisig,
+        &signer_refs,
+    );
+    env.process(
+        &[set_authority, accept],
+        &[&round.owner, &signers[0], &signers[1]],
+    )
+    .await
+    .unwrap();
+
+    let projects = [project_a.project, project_b.project];
+    let settle_matching = instruction::with_multisig_signers(
+        instruction::settle_matching(&env.program_id, &round.round, &multisig, &projects),
+        &multisig,
+        &signer_refs[..1],
+    );
+    let result = env.process(&[settle_matching], &[&signers[0]]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+    let settle_matching = instruction::with_multisig_signers(
+        instruction::settle_matching(&env.program_id, &round.round, &multisig, &projects),
+        &multisig,
+        &signer_refs,
+    );
+    env.process(&[settle_matching], &[&signers[0], &signers[1]])
+        .await
+        .unwrap();
+    assert!(env.get_round(&round).await.matching_settled);
+    assert_eq!(env.get_project(&project_a).await.matched, 600);
+    assert_eq!(env.get_project(&project_b).await.matched, 400);
+
+    for project in [&project_a, &project_b] {
+        let (_, result) = env.withdraw(&round, project).await;
+        result.unwrap();
+    }
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 0);
+}
+
+#[tokio::test]
+async fn test_uncapped_round_cannot_settle() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    env.end_round(&round).await;
+
+    let settle_matching =
+        instruction::settle_matching(&env.program_id, &round.round, &round.owner.pubkey(), &[]);
+    let result = env.process(&[settle_matching], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::InvalidMatchingCap);
+
+    let voter_a = Pubkey::new_unique();
+    let voter_b = Pubkey::new_unique();
+    let init_voter_pair = instruction::init_voter_pair(
+        &env.program_id,
+        &round.round,
+        &voter_a,
+        &voter_b,
+        &env.ctx.payer.pubkey(),
+    );
+    let result = env.process(&[init_voter_pair], &[]).await;
+    assert_qf_error(result, 0, QFError::MatchingAlgorithmMismatch);
+}
+
+#[tokio::test]
+async fn test_pairwise_matching() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            matching_algorithm: MatchingAlgorithm::Pairwise,
+            pairwise_threshold: 100,
+            ..env.config()
+        })
+        .await;
+    env.donate(&round, 1_000).await;
+
+    let project_a = env.register_project(&round).await;
+    let project_b = env.register_project(&round).await;
+    let voter_a = env.create_voter(&project_a, 100).await;
+    env.vote(&round, &project_a, &voter_a, 100).await.unwrap();
+    let voter_b = env.create_voter(&project_a, 400).await;
+    env.vote(&round, &project_a, &voter_b, 400).await.unwrap();
+    let voter_c = env.create_voter(&project_b, 400).await;
+    env.vote(&round, &project_b, &voter_c, 400).await.unwrap();
+    // a voter account without votes does not form a pair
+    let idle = env.create_voter(&project_a, 0).await;
+    env.end_round(&round).await;
+    assert_eq!(env.get_round(&round).await.pair_count, 1);
+
+    let payer = env.ctx.payer.pubkey();
+    let mut init_voter_pair = instruction::init_voter_pair(
+        &env.program_id,
+        &round.round,
+        &voter_a.wallet.pubkey(),
+        &voter_b.wallet.pubkey(),
+        &payer,
+    );
+    init_voter_pair.accounts.swap(2, 3);
+    let result = env.process(&[init_voter_pair], &[]).await;
+    assert_qf_error(result, 0, QFError::VoterPairMismatch);
+
+    for (a, b) in [(&voter_a, &voter_b), (&voter_a, &idle)] {
+        let init_voter_pair = instruction::init_voter_pair(
+            &env.program_id,
+            &round.round,
+            &a.wallet.pubkey(),
+            &b.wallet.pubkey(),
+            &payer,
+        );
+        env.process(&[init_voter_pair], &[]).await.unwrap();
+    }
+
+    let (_, result) = env.withdraw(&round, &project_a).await;
+    assert_qf_error(result, 0, QFError::MatchingNotSettled);
+
+    let distribute = instruction::distribute_voter_pair(
+        &env.program_id,
+        &round.round,
+        &project_a.project,
+        &voter_a.wallet.pubkey(),
+        &voter_b.wallet.pubkey(),
+    );
+    let result = env.process(slice::from_ref(&distribute), &[]).await;
+    assert_qf_error(result, 0, QFError::PairwiseNotAccumulated);
+
+    let accumulate = instruction::accumulate_voter_pair(
+        &env.program_id,
+        &round.round,
+        &project_a.project,
+        &voter_a.wallet.pubkey(),
+        &idle.wallet.pubkey(),
+    );
+    let result = env.process(&[accumulate], &[]).await;
+    assert_qf_error(result, 0, QFError::PairNotOverlapped);
+
+    let accumulate = instruction::accumulate_voter_pair(
+        &env.program_id,
+        &round.round,
+        &project_a.project,
+        &voter_a.wallet.pubkey(),
+        &voter_b.wallet.pubkey(),
+    );
+    env.process(slice::from_ref(&accumulate), &[])
+        .await
+        .unwrap();
+    env.refresh_blockhash().await;
+    let result = env.process(&[accumulate], &[]).await;
+    assert_qf_error(result, 0, QFError::PairAlreadyProcessed);
+
+    env.refresh_blockhash().await;
+    env.process(slice::from_ref(&distribute), &[])
+        .await
+        .unwrap();
+    env.refresh_blockhash().await;
+    let result = env.process(&[distribute], &[]).await;
+    assert_qf_error(result, 0, QFError::PairAlreadyProcessed);
+
+    let state = env.get_round(&round).await;
+    assert_eq!(state.pairs_distributed, state.pair_count);
+    let projects = vec![
+        env.get_project(&project_a).await,
+        env.get_project(&project_b).await,
+    ];
+    let payouts = payout::simulate_payouts(&state, &projects).unwrap();
+    for (project, payout) in [(&project_a, payouts[0]), (&project_b, payouts[1])] {
+        let (to, result) = env.withdraw(&round, project).await;
+        result.unwrap();
+        assert_eq!(env.token_balance(&to).await, payout.amount);
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_round() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    let from = env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+
+    let (mint, price_feed) = env.add_mint(&round, 2 * PRICE_SCALE).await;
+    let payer = env.ctx.payer.pubkey();
+    let from_b = env.create_token_account_of(&mint, &payer, 50).await;
+    let donate = instruction::donate_in_mint(
+        &env.program_id,
+        &round.round,
+        &from_b,
+        &mint,
+        &payer,
+        &price_feed,
+        50,
+        DECIMALS,
+    );
+    env.process(&[donate], &[]).await.unwrap();
+    let voter_b = env.create_voter_of(&mint, &project, 30).await;
+    let vote = instruction::vote_in_mint(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &voter_b.token_account,
+        &mint,
+        &voter_b.wallet.pubkey(),
+        &price_feed,
+        30,
+        DECIMALS,
+    );
+    env.process(&[vote], &[&voter_b.wallet]).await.unwrap();
+
+    let refund_vote = instruction::refund_vote(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &project.project,
+        &voter.wallet.pubkey(),
+        &voter.token_account,
+    );
+    let result = env.process(slice::from_ref(&refund_vote), &[]).await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+
+    let cancel_round =
+        instruction::cancel_round(&env.program_id, &round.round, &project.owner.pubkey());
+    let result = env.process(&[cancel_round], &[&project.owner]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+    let cancel_round =
+        instruction::cancel_round(&env.program_id, &round.round, &round.owner.pubkey());
+    env.process(&[cancel_round], &[&round.owner]).await.unwrap();
+    assert_eq!(env.get_round(&round).await.status, RoundStatus::Cancelled);
+
+    let result = env.vote(&round, &project, &voter, 0).await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+
+    let not_donor = env.create_token_account(&round.owner.pubkey(), 0).await;
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &not_donor);
+    let result = env.process(&[refund_donation], &[]).await;
+    assert_qf_error(result, 0, QFError::DonorMismatch);
+
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &from);
+    env.process(&[refund_donation], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&from).await, 1_000);
+
+    env.refresh_blockhash().await;
+    env.process(&[refund_vote], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&voter.token_account).await, 100);
+    assert_eq!(env.get_project(&project).await.votes, 60);
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 0);
+
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &mint, &payer, &from_b);
+    env.process(&[refund_donation], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&from_b).await, 50);
+    let refund_vote = instruction::refund_vote(
+        &env.program_id,
+        &round.round,
+        &mint,
+        &project.project,
+        &voter_b.wallet.pubkey(),
+        &voter_b.token_account,
+    );
+    env.process(&[refund_vote], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&voter_b.token_account).await, 30);
+    assert_eq!(env.get_project(&project).await.votes, 0);
+
+    let state = env.get_round(&round).await;
+    assert_eq!((state.fund, state.votes, state.deposited), (0, 0, 0));
+    assert_eq!(env.get_round_mint(&round, &mint).await.deposited, 0);
+    let vault_b = instruction::get_vault_address(&env.program_id, &round.round, &mint);
+    assert_eq!(env.token_balance(&vault_b).await, 0);
+}
+
+#[tokio::test]
+async fn test_multi_token_round() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            fee_bps: 500,
+            ..env.config()
+        })
+        .await;
+    env.donate(&round, 1_000).await;
+    let (mint, price_feed) = env.add_mint(&round, PRICE_SCALE).await;
+    assert_eq!(env.get_round(&round).await.mint_count, 1);
+    let payer = env.ctx.payer.pubkey();
+
+    let set_price = instruction::set_price(&env.program_id, &price_feed, &round.owner.pubkey(), 1);
+    let result = env.process(&[set_price], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+    let set_price = instruction::set_price(&env.program_id, &price_feed, &payer, 0);
+    let result = env.process(&[set_price], &[]).await;
+    assert_qf_error(result, 0, QFError::InvalidPrice);
+    let set_price = instruction::set_price(&env.program_id, &price_feed, &payer, 2 * PRICE_SCALE);
+    env.process(&[set_price], &[]).await.unwrap();
+
+    let fee_recipient = env.create_token_account(&round.owner.pubkey(), 0).await;
+    for accepted in [env.mint, mint] {
+        let add_mint = instruction::add_mint(
+            &env.program_id,
+            &round.round,
+            &round.owner.pubkey(),
+            &accepted,
+            &price_feed,
+            &fee_recipient,
+            &payer,
+        );
+        let result = env.process(&[add_mint], &[&round.owner]).await;
+        assert_qf_error(result, 0, QFError::MintAlreadyAccepted);
+    }
+
+    let project_a = env.register_project(&round).await;
+    let project_b = env.register_project(&round).await;
+    let voter_a = env.create_voter(&project_a, 100).await;
+    env.vote(&round, &project_a, &voter_a, 100).await.unwrap();
+
+    let voter_b = env.create_voter_of(&mint, &project_b, 200).await;
+    let program_id = env.program_id;
+    let vote_b = |price_feed: &Pubkey| {
+        instruction::vote_in_mint(
+            &program_id,
+            &round.round,
+            &project_b.project,
+            &voter_b.token_account,
+            &mint,
+            &voter_b.wallet.pubkey(),
+            price_feed,
+            200,
+            DECIMALS,
+        )
+    };
+    let result = env
+        .process(&[vote_b(&Pubkey::new_unique())], &[&voter_b.wallet])
+        .await;
+    assert_qf_error(result, 0, QFError::PriceFeedMismatch);
+    let mut vote = vote_b(&price_feed);
+    vote.accounts[5].pubkey = env.vault(&round);
+    let result = env.process(&[vote], &[&voter_b.wallet]).await;
+    assert_qf_error(result, 0, QFError::VaultMismatch);
+    let vote = vote_b(&price_feed);
+    env.process(&[vote], &[&voter_b.wallet]).await.unwrap();
+
+    // the round mint of another mint is rejected
+    let other_mint = env.create_mint().await;
+    let from = env.create_token_account_of(&other_mint, &payer, 50).await;
+    let mut donate = instruction::donate_in_mint(
+        &env.program_id,
+        &round.round,
+        &from,
+        &other_mint,
+        &payer,
+        &price_feed,
+        50,
+        DECIMALS,
+    );
+    donate.accounts[10].pubkey =
+        instruction::get_round_mint_address(&env.program_id, &round.round, &mint);
+    let result = env.process(&[donate], &[]).await;
+    assert_qf_error(result, 0, QFError::MintMismatch);
+
+    let from = env.create_token_account_of(&mint, &payer, 50).await;
+    let donate = instruction::donate_in_mint(
+        &env.program_id,
+        &round.round,
+        &from,
+        &mint,
+        &payer,
+        &price_feed,
+        50,
+        DECIMALS,
+    );
+    env.process(&[donate], &[]).await.unwrap();
+
+    let state = env.get_round(&round).await;
+    assert_eq!(state.fund, 1_100);
+    assert_eq!(state.votes, 500);
+    assert_eq!(state.deposited, 1_100);
+    let round_mint = env.get_round_mint(&round, &mint).await;
+    assert_eq!(round_mint.deposited, 250);
+    assert_eq!(env.get_project(&project_b).await.votes, 400);
+
+    env.end_round(&round).await;
+    let state = env.get_round(&round).await;
+    let projects = vec![
+        env.get_project(&project_a).await,
+        env.get_project(&project_b).await,
+    ];
+    let payouts = payout::simulate_payouts(&state, &projects).unwrap();
+
+    let to = env
+        .create_token_account_of(&mint, &project_a.owner.pubkey(), 0)
+        .await;
+    let withdraw_mint = instruction::withdraw_mint(
+        &env.program_id,
+        &round.round,
+        &mint,
+        &project_a.project,
+        &project_a.owner.pubkey(),
+        &to,
+        None,
+    );
+    let result = env
+        .process(slice::from_ref(&withdraw_mint), &[&project_a.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::ProjectNotWithdrawn);
+
+    // an attestation signed with the project key for the mint does not take
+    // the address of the withdraw receipt
+    env.attest(&project_a.keypair, &mint, 10_000).await.unwrap();
+
+    let mut vault_b_paid = 0;
+    for (project, payout) in [(&project_a, payouts[0]), (&project_b, payouts[1])] {
+        let (to, result) = env.withdraw(&round, project).await;
+        result.unwrap();
+        assert_eq!(
+            env.token_balance(&to).await,
+            payout::vault_share(&state, payout.amount, 1_100, 1_000).unwrap()
+        );
+
+        let to = env
+            .create_token_account_of(&mint, &project.owner.pubkey(), 0)
+            .await;
+        let withdraw_mint = instruction::withdraw_mint(
+            &env.program_id,
+            &round.round,
+            &mint,
+            &project.project,
+            &project.owner.pubkey(),
+            &to,
+            None,
+        );
+        // the project owner pays for the withdraw receipt
+        let fund_owner =
+            system_instruction::transfer(&payer, &project.owner.pubkey(), 1_000_000_000);
+        env.process(&[fund_owner, withdraw_mint], &[&project.owner])
+            .await
+            .unwrap();
+        let paid = payout::vault_share(&state, payout.amount, 250, 50).unwrap();
+        assert_eq!(env.token_balance(&to).await, paid);
+        vault_b_paid += paid;
+    }
+
+    env.refresh_blockhash().await;
+    let result = env.process(&[withdraw_mint], &[&project_a.owner]).await;
+    assert_qf_error(result, 0, QFError::ProjectAlreadyWithdraw);
+
+    let state = env.get_round(&round).await;
+    assert_eq!(state.fee_total, payouts[0].fee + payouts[1].fee);
+    let withdraw_mint_fee = instruction::withdraw_mint_fee(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &mint,
+        &round_mint.fee_recipient,
+    );
+    env.process(slice::from_ref(&withdraw_mint_fee), &[&round.owner])
+        .await
+        .unwrap();
+    let fee = payout::vault_share(&state, state.fee_total, 250, 50).unwrap();
+    assert_eq!(env.token_balance(&round_mint.fee_recipient).await, fee);
+    env.refresh_blockhash().await;
+    let result = env.process(&[withdraw_mint_fee], &[&round.owner]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InsufficientFunds)
+    );
+
+    let vault_b = instruction::get_vault_address(&env.program_id, &round.round, &mint);
+    assert_eq!(env.token_balance(&vault_b).await, 250 - vault_b_paid - fee);
+
+    // the vault of the mint is swept and closed with the round
+    let withdraw_fee = instruction::withdraw_fee(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &env.mint,
+        &round.fee_recipient,
+    );
+    env.process(&[withdraw_fee], &[&round.owner]).await.unwrap();
+    let owner = round.owner.pubkey();
+    let to = env.create_token_account(&owner, 0).await;
+    let to_b = env.create_token_account_of(&mint, &owner, 0).await;
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &to,
+        &owner,
+        &[],
+    );
+    let result = env.process(&[close_round], &[&round.owner]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+    let residual_b = env.token_balance(&vault_b).await;
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &to,
+        &owner,
+        &[(mint, to_b)],
+    );
+    env.process(&[close_round], &[&round.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&to_b).await, residual_b);
+    let round_mint = instruction::get_round_mint_address(&env.program_id, &round.round, &mint);
+    for account in [vault_b, round_mint, round.round] {
+        let account = env.ctx.banks_client.get_account(account).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_native_round() {
+    let mut env = Env::new().await;
+    env.mint = spl_token::native_mint::id();
+    let round = env.start_round(env.config()).await;
+    let payer = env.ctx.payer.pubkey();
+
+    let donate = instruction::donate_native(&env.program_id, &round.round, &payer, 1_000_000);
+    env.process(&[donate], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 1_000_000);
+    assert_eq!(env.get_round(&round).await.fund, 1_000_000);
+
+    let project = env.register_project(&round).await;
+    let wallet = Keypair::new();
+    let fund_wallet = system_instruction::transfer(&payer, &wallet.pubkey(), 10_000_000);
+    let init_voter =
+        instruction::init_voter(&env.program_id, &project.project, &wallet.pubkey(), &payer);
+    let init_other_voter =
+        instruction::init_voter(&env.program_id, &project.project, &payer, &payer);
+    env.process(&[fund_wallet, init_voter, init_other_voter], &[&wallet])
+        .await
+        .unwrap();
+
+    let mut vote = instruction::vote_native(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &wallet.pubkey(),
+        4_000_000,
+    );
+    vote.accounts[2].pubkey =
+        instruction::get_voter_address(&env.program_id, &project.project, &payer);
+    let result = env.process(&[vote], &[&wallet]).await;
+    assert_qf_error(result, 0, QFError::VoterMismatch);
+
+    let vote = instruction::vote_native(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &wallet.pubkey(),
+        4_000_000,
+    );
+    env.process(&[vote], &[&wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 5_000_000);
+    assert_eq!(env.get_project(&project).await.votes, 4_000_000);
+    let state = env.get_round(&round).await;
+    assert_eq!((state.votes, state.deposited), (4_000_000, 5_000_000));
+
+    let cancel_round =
+        instruction::cancel_round(&env.program_id, &round.round, &round.owner.pubkey());
+    env.process(&[cancel_round], &[&round.owner]).await.unwrap();
+
+    // refunds are paid in wrapped SOL
+    let to = env.create_token_account(&payer, 0).await;
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &to);
+    env.process(&[refund_donation], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&to).await, 1_000_000);
+
+    let to = env.create_token_account(&wallet.pubkey(), 0).await;
+    let refund_vote = instruction::refund_vote(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &project.project,
+        &wallet.pubkey(),
+        &to,
+    );
+    env.process(&[refund_vote], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&to).await, 4_000_000);
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 0);
+}
+
+#[tokio::test]
+async fn test_voter_per_wallet() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    let project = env.register_project(&round).await;
+
+    let wallet = Keypair::new();
+    let mut init_voter = instruction::init_voter(
+        &env.program_id,
+        &project.project,
+        &wallet.pubkey(),
+        &env.ctx.payer.pubkey(),
+    );
+    init_voter.accounts[1].is_signer = false;
+    let result = env.process(&[init_voter], &[]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+
+    // votes from every token account of a wallet go to the same voter
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+    let second = env.create_token_account(&voter.wallet.pubkey(), 300).await;
+    let vote = instruction::vote(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &second,
+        &env.mint,
+        &voter.wallet.pubkey(),
+        300,
+        DECIMALS,
+    );
+    env.process(&[vote], &[&voter.wallet]).await.unwrap();
+    let state = env.get_voter(&project, &voter.wallet.pubkey()).await;
+    assert_eq!(
+        (state.votes, state.amount, state.mint),
+        (400, 400, env.mint)
+    );
+    assert_eq!(env.get_project(&project).await.voter_count, 1);
+
+    let (mint, price_feed) = env.add_mint(&round, PRICE_SCALE).await;
+    let other = env
+        .create_token_account_of(&mint, &voter.wallet.pubkey(), 10)
+        .await;
+    let vote = instruction::vote_in_mint(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &other,
+        &mint,
+        &voter.wallet.pubkey(),
+        &price_feed,
+        10,
+        DECIMALS,
+    );
+    let result = env.process(&[vote], &[&voter.wallet]).await;
+    assert_qf_error(result, 0, QFError::MintMismatch);
+}
+
+#[tokio::test]
+async fn test_identity_gate() {
+    let mut env = Env::new().await;
+    let issuer = Keypair::new();
+    let round = env
+        .start_round(RoundConfig {
+            identity_issuer: issuer.pubkey(),
+            ..env.config()
+        })
+        .await;
+    env.donate(&round, 1_000).await;
+    let project_a = env.register_project(&round).await;
+    let project_b = env.register_project(&round).await;
+
+    let attested = env.create_voter(&project_a, 100).await;
+    let mut attest = instruction::attest(
+        &env.program_id,
+        &issuer.pubkey(),
+        &attested.wallet.pubkey(),
+        &env.ctx.payer.pubkey(),
+        10_000,
+    );
+    attest.accounts[1].is_signer = false;
+    let result = env.process(&[attest], &[]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+    env.attest(&issuer, &attested.wallet.pubkey(), 10_000)
+        .await
+        .unwrap();
+
+    // the attestation of another issuer does not count
+    let result = env
+        .vote_attested(&round, &project_a, &attested, &Pubkey::new_unique(), 100)
+        .await;
+    assert_qf_error(result, 0, QFError::AttestationMismatch);
+    let result = env.vote(&round, &project_a, &attested, 100).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+    env.vote_attested(&round, &project_a, &attested, &issuer.pubkey(), 100)
+        .await
+        .unwrap();
+    let state = env.get_voter(&project_a, &attested.wallet.pubkey()).await;
+    assert_eq!(state.weight_bps, 10_000);
+
+    // unattested votes are taken but not matched
+    let unattested = env.create_voter(&project_b, 400).await;
+    env.vote_attested(&round, &project_b, &unattested, &issuer.pubkey(), 400)
+        .await
+        .unwrap();
+    let state = env.get_voter(&project_b, &unattested.wallet.pubkey()).await;
+    assert_eq!((state.votes, state.weight_bps), (400, 0));
+    let state = env.get_project(&project_b).await;
+    assert_eq!((state.votes, state.area), (400, U256::zero()));
+
+    // votes after a revoke are not matched either, the votes before keep
+    // their weight
+    let revoked = env.create_voter(&project_a, 110).await;
+    env.attest(&issuer, &revoked.wallet.pubkey(), 10_000)
+        .await
+        .unwrap();
+    env.vote_attested(&round, &project_a, &revoked, &issuer.pubkey(), 64)
+        .await
+        .unwrap();
+    let revoke = instruction::revoke_attestation(
+        &env.program_id,
+        &issuer.pubkey(),
+        &revoked.wallet.pubkey(),
+        &env.ctx.payer.pubkey(),
+    );
+    env.process(&[revoke], &[&issuer]).await.unwrap();
+    env.vote_attested(&round, &project_a, &revoked, &issuer.pubkey(), 46)
+        .await
+        .unwrap();
+    let state = env.get_voter(&project_a, &revoked.wallet.pubkey()).await;
+    assert_eq!((state.votes, state.weight_bps), (110, 0));
+    // (10 + 8)^2
+    let state = env.get_project(&project_a).await;
+    assert_eq!(state.votes, 210);
+    assert_eq!(state.area, U256::from(324) * U256::from(ONE));
+
+    env.end_round(&round).await;
+    let (to_a, result) = env.withdraw(&round, &project_a).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to_a).await, 1_210);
+    let (to_b, result) = env.withdraw(&round, &project_b).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to_b).await, 400);
+}
+
+#[tokio::test]
+async fn test_trust_scores() {
+    let mut env = Env::new().await;
+    let issuer = Keypair::new();
+    let round = env
+        .start_round(RoundConfig {
+            identity_issuer: issuer.pubkey(),
+            ..env.config()
+        })
+        .await;
+    let project = env.register_project(&round).await;
+    let low = env.create_voter(&project, 121).await;
+    let high = env.create_voter(&project, 100).await;
+
+    let result = env.attest(&issuer, &low.wallet.pubkey(), 10_001).await;
+    assert_qf_error(result, 0, QFError::InvalidTrustScore);
+    env.attest(&issuer, &low.wallet.pubkey(), 2_500)
+        .await
+        .unwrap();
+    env.attest(&issuer, &high.wallet.pubkey(), 10_000)
+        .await
+        .unwrap();
+
+    // sqrt(100) * 25%
+    env.vote_attested(&round, &project, &low, &issuer.pubkey(), 100)
+        .await
+        .unwrap();
+    let state = env.get_project(&project).await;
+    assert_eq!(state.votes, 100);
+    assert_eq!(state.area, U256::from(625) * U256::from(ONE) / 100);
+
+    // (2.5 + 10)^2
+    env.vote_attested(&round, &project, &high, &issuer.pubkey(), 100)
+        .await
+        .unwrap();
+    let state = env.get_project(&project).await;
+    assert_eq!(state.area, U256::from(15_625) * U256::from(ONE) / 100);
+
+    // a new score only applies to the votes cast after it,
+    // (2.5 + (11 - 10) * 50% + 10)^2
+    env.attest(&issuer, &low.wallet.pubkey(), 5_000)
+        .await
+        .unwrap();
+    env.vote_attested(&round, &project, &low, &issuer.pubkey(), 21)
+        .await
+        .unwrap();
+    let state = env.get_voter(&project, &low.wallet.pubkey()).await;
+    assert_eq!((state.votes, state.weight_bps), (121, 5_000));
+    let state = env.get_project(&project).await;
+    assert_eq!(state.votes, 221);
+    assert_eq!(state.area, U256::from(169) * U256::from(ONE));
+}
+
+#[tokio::test]
+async fn test_project_approval() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            curated: true,
+            ..env.config()
+        })
+        .await;
+    let project_a = env.register_project(&round).await;
+    let project_b = env.register_project(&round).await;
+    let project_c = env.register_project(&round).await;
+    assert_eq!(
+        env.get_project(&project_a).await.status,
+        ProjectStatus::Pending
+    );
+
+    let voter = env.create_voter(&project_a, 100).await;
+    let result = env.vote(&round, &project_a, &voter, 100).await;
+    assert_qf_error(result, 0, QFError::ProjectNotApproved);
+
+    let curator = Keypair::new();
+    let approve = instruction::approve_project(
+        &env.program_id,
+        &round.round,
+        &project_a.project,
+        &curator.pubkey(),
+    );
+    let result = env.process(&[approve], &[&curator]).await;
+    assert_qf_error(result, 0, QFError::NotCurator);
+
+    let add_curator = instruction::add_curator(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &curator.pubkey(),
+        &env.ctx.payer.pubkey(),
+    );
+    env.process(&[add_curator], &[&round.owner]).await.unwrap();
+    env.refresh_blockhash().await;
+    let approve = instruction::approve_project(
+        &env.program_id,
+        &round.round,
+        &project_a.project,
+        &curator.pubkey(),
+    );
+    env.process(&[approve], &[&curator]).await.unwrap();
+    env.vote(&round, &project_a, &voter, 100).await.unwrap();
+    assert_eq!(env.get_project(&project_a).await.votes, 100);
+
+    let reject = instruction::reject_project(
+        &env.program_id,
+        &round.round,
+        &project_b.project,
+        &round.owner.pubkey(),
+    );
+    env.process(&[reject], &[&round.owner]).await.unwrap();
+    assert_eq!(
+        env.get_project(&project_b).await.status,
+        ProjectStatus::Rejected
+    );
+    let approve = instruction::approve_project(
+        &env.program_id,
+        &round.round,
+        &project_b.project,
+        &round.owner.pubkey(),
+    );
+    let result = env.process(&[approve], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::ProjectNotPending);
+
+    let remove_curator = instruction::remove_curator(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &curator.pubkey(),
+        &round.owner.pubkey(),
+    );
+    env.process(&[remove_curator], &[&round.owner])
+        .await
+        .unwrap();
+    let approve = instruction::approve_project(
+        &env.program_id,
+        &round.round,
+        &project_c.project,
+        &curator.pubkey(),
+    );
+    let result = env.process(&[approve], &[&curator]).await;
+    assert_qf_error(result, 0, QFError::NotCurator);
+}
+
+#[tokio::test]
+async fn test_project_metadata() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    env.donate(&round, 1_000).await;
+
+    let (_, result) = env
+        .try_register_project_with(
+            &round,
+            &ProjectMetadata {
+                name: "a".repeat(MAX_PROJECT_NAME_LEN + 1),
+                ..ProjectMetadata::default()
+            },
+        )
+        .await;
+    assert_qf_error(result, 1, QFError::InvalidMetadata);
+
+    let metadata = ProjectMetadata {
+        name: "Open Source Tooling".to_string(),
+        description_uri: "https://example.com/tooling.json".to_string(),
+        image_uri: String::new(),
+        payout: Pubkey::default(),
+    };
+    let (project, result) = env.try_register_project_with(&round, &metadata).await;
+    result.unwrap();
+    assert_eq!(
+        env.get_project_metadata(&project).await,
+        (Project::account_len(&metadata), metadata.clone())
+    );
+
+    // a larger section is paid by the owner
+    let payout = env.create_token_account(&project.owner.pubkey(), 0).await;
+    let updated = ProjectMetadata {
+        image_uri: "https://example.com/tooling.png".to_string(),
+        payout,
+        ..metadata
+    };
+    // the payout account has to be a token account of the round mint
+    let other_mint = env.create_mint().await;
+    let other_payout = env
+        .create_token_account_of(&other_mint, &project.owner.pubkey(), 0)
+        .await;
+    let cases = [
+        (Pubkey::new_unique(), QFError::UnexpectedTokenProgramID),
+        (other_payout, QFError::MintMismatch),
+    ];
+    for (payout, error) in cases {
+        let invalid = ProjectMetadata {
+            payout,
+            ..updated.clone()
+        };
+        let (_, result) = env.try_register_project_with(&round, &invalid).await;
+        assert_qf_error(result, 1, error.clone());
+        let update = instruction::update_project_metadata(
+            &env.program_id,
+            &round.round,
+            &project.project,
+            &project.owner.pubkey(),
+            &invalid,
+        );
+        let result = env.process(&[update], &[&project.owner]).await;
+        assert_qf_error(result, 0, error);
+    }
+    let mut update = instruction::update_project_metadata(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &project.owner.pubkey(),
+        &updated,
+    );
+    update.accounts[5].pubkey = Pubkey::new_unique();
+    let result = env.process(&[update], &[&project.owner]).await;
+    assert_qf_error(result, 0, QFError::PayoutMismatch);
+
+    let stranger = Keypair::new();
+    let update = instruction::update_project_metadata(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &stranger.pubkey(),
+        &updated,
+    );
+    let result = env.process(&[update], &[&stranger]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+
+    let fund_owner = system_instruction::transfer(
+        &env.ctx.payer.pubkey(),
+        &project.owner.pubkey(),
+        1_000_000_000,
+    );
+    let update = instruction::update_project_metadata(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &project.owner.pubkey(),
+        &updated,
+    );
+    env.process(&[fund_owner, update], &[&project.owner])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_project_metadata(&project).await,
+        (Project::account_len(&updated), updated)
+    );
+    assert_eq!(env.get_project(&project).await.round, round.round);
+
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+    env.end_round(&round).await;
+
+    let (_, result) = env.withdraw(&round, &project).await;
+    assert_qf_error(result, 0, QFError::PayoutMismatch);
+    let withdraw = instruction::withdraw(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &project.project,
+        &project.owner.pubkey(),
+        &payout,
+    );
+    env.process(&[withdraw], &[&project.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&payout).await, 1_100);
+}
+
+#[tokio::test]
+async fn test_round_authority() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+
+    let new_owner = Keypair::new();
+    let accept =
+        instruction::accept_round_authority(&env.program_id, &round.round, &new_owner.pubkey());
+    let result = env.process(&[accept], &[&new_owner]).await;
+    assert_qf_error(result, 0, QFError::NoPendingAuthority);
+
+    let set_authority = instruction::set_round_authority(
+        &env.program_id,
+        &round.round,
+        &new_owner.pubkey(),
+        &new_owner.pubkey(),
+    );
+    let result = env.process(&[set_authority], &[&new_owner]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+
+    let set_authority = instruction::set_round_authority(
+        &env.program_id,
+        &round.round,
+        &round.owner.pubkey(),
+        &new_owner.pubkey(),
+    );
+    env.process(&[set_authority], &[&round.owner])
+        .await
+        .unwrap();
+    assert_eq!(env.get_round(&round).await.owner, round.owner.pubkey());
+    let stranger = Keypair::new();
+    let accept =
+        instruction::accept_round_authority(&env.program_id, &round.round, &stranger.pubkey());
+    let result = env.process(&[accept], &[&stranger]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+    env.refresh_blockhash().await;
+    let accept =
+        instruction::accept_round_authority(&env.program_id, &round.round, &new_owner.pubkey());
+    env.process(&[accept], &[&new_owner]).await.unwrap();
+    let state = env.get_round(&round).await;
+    assert_eq!(
+        (state.owner, state.pending_owner),
+        (new_owner.pubkey(), Pubkey::default())
+    );
+
+    // a 2 of 3 multisig takes over the round
+    let signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let signer_keys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+    let signer_refs: Vec<&Pubkey> = signer_keys.iter().collect();
+    let multisig = env.create_multisig(&signer_refs, 2).await;
+    let set_authority = instruction::set_round_authority(
+        &env.program_id,
+        &round.round,
+        &new_owner.pubkey(),
+        &multisig,
+    );
+    env.process(&[set_authority], &[&new_owner]).await.unwrap();
+    let accept = instruction::with_multisig_signers(
+        instruction::accept_round_authority(&env.program_id, &round.round, &multisig),
+        &multisig,
+        &signer_refs[..1],
+    );
+    let result = env.process(&[accept], &[&signers[0]]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+    let accept = instruction::with_multisig_signers(
+        instruction::accept_round_authority(&env.program_id, &round.round, &multisig),
+        &multisig,
+        &signer_refs[1..],
+    );
+    env.process(&[accept], &[&signers[1], &signers[2]])
+        .await
+        .unwrap();
+
+    let end_round = instruction::end_round(&env.program_id, &round.round, &new_owner.pubkey());
+    let result = env.process(&[end_round], &[&new_owner]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+    let end_round = instruction::with_multisig_signers(
+        instruction::end_round(&env.program_id, &round.round, &multisig),
+        &multisig,
+        &[&signer_keys[0], &signer_keys[2]],
+    );
+    env.process(&[end_round], &[&signers[0], &signers[2]])
+        .await
+        .unwrap();
+    assert_eq!(env.get_round(&round).await.status, RoundStatus::Finished);
+}
+
+#[tokio::test]
+async fn test_round_creators() {
+    let mut env = Env::new().await;
+    let config = env.config();
+    let (round, test_round, mut instructions) = env.start_round_instructions(config).await;
+    instructions[1].accounts[1].is_signer = false;
+    let result = env.process(&instructions, &[&round]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(1, InstructionError::MissingRequiredSignature)
+    );
+
+    // only the upgrade authority creates the config
+    let admin = Keypair::new();
+    let payer = env.ctx.payer.pubkey();
+    let init_config = instruction::init_config(&env.program_id, &admin.pubkey(), &payer);
+    let result = env.process(slice::from_ref(&init_config), &[&admin]).await;
+    assert_qf_error(result, 0, QFError::UpgradeAuthorityMismatch);
+    env.set_upgrade_authority(&Pubkey::new_unique());
+    env.refresh_blockhash().await;
+    let result = env.process(slice::from_ref(&init_config), &[&admin]).await;
+    assert_qf_error(result, 0, QFError::UpgradeAuthorityMismatch);
+
+    // once the config exists only allowed creators start rounds
+    env.set_upgrade_authority(&admin.pubkey());
+    env.refresh_blockhash().await;
+    env.process(&[init_config], &[&admin]).await.unwrap();
+    let (round, test_round_b, instructions) = env.start_round_instructions(config).await;
+    let result = env
+        .process(&instructions, &[&round, &test_round_b.owner])
+        .await;
+    assert_qf_error(result, 1, QFError::CreatorNotAllowed);
+
+    let add_creator = instruction::add_creator(
+        &env.program_id,
+        &test_round.owner.pubkey(),
+        &test_round_b.owner.pubkey(),
+        &payer,
+    );
+    let result = env.process(&[add_creator], &[&test_round.owner]).await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+    let add_creator = instruction::add_creator(
+        &env.program_id,
+        &admin.pubkey(),
+        &test_round_b.owner.pubkey(),
+        &payer,
+    );
+    env.process(&[add_creator], &[&admin]).await.unwrap();
+    env.refresh_blockhash().await;
+    env.process(&instructions, &[&round, &test_round_b.owner])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_round(&test_round_b).await.owner,
+        test_round_b.owner.pubkey()
+    );
+
+    let remove_creator = instruction::remove_creator(
+        &env.program_id,
+        &admin.pubkey(),
+        &test_round_b.owner.pubkey(),
+        &payer,
+    );
+    env.process(&[remove_creator], &[&admin]).await.unwrap();
+    let creator = instruction::get_creator_address(&env.program_id, &test_round_b.owner.pubkey());
+    let account = env.ctx.banks_client.get_account(creator).await.unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn test_empty_round() {
+    let mut env = Env::new().await;
+    let payer = env.ctx.payer.pubkey();
+
+    // nobody voted, the project withdraws nothing and the donor is refunded
+    let round = env.start_round(env.config()).await;
+    let from = env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    env.end_round(&round).await;
+    let (to, result) = env.withdraw(&round, &project).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to).await, 0);
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &from);
+    env.process(&[refund_donation], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&from).await, 1_000);
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 0);
+
+    // donors of a round with matched votes are not refunded
+    let round = env.start_round(env.config()).await;
+    let from = env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+    env.end_round(&round).await;
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &from);
+    let result = env.process(&[refund_donation], &[]).await;
+    assert_qf_error(result, 0, QFError::RoundNotEmpty);
+
+    // the pool is split equally among the projects
+    let mut config = env.config();
+    config.empty_round_policy = EmptyRoundPolicy::SplitEqually;
+    let round = env.start_round(config).await;
+    let from = env.donate(&round, 1_000).await;
+    let projects = [
+        env.register_project(&round).await,
+        env.register_project(&round).await,
+        env.register_project(&round).await,
+    ];
+    env.end_round(&round).await;
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &from);
+    let result = env.process(&[refund_donation], &[]).await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+    for project in projects.iter() {
+        let (to, result) = env.withdraw(&round, project).await;
+        result.unwrap();
+        assert_eq!(env.token_balance(&to).await, 333);
+    }
+
+    // without an approved project to split the pool the donors are refunded
+    let round = env
+        .start_round(RoundConfig {
+            curated: true,
+            empty_round_policy: EmptyRoundPolicy::SplitEqually,
+            ..env.config()
+        })
+        .await;
+    let from = env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    env.end_round(&round).await;
+    let (to, result) = env.withdraw(&round, &project).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to).await, 0);
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &from);
+    env.process(&[refund_donation], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&from).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_roll_over() {
+    let mut env = Env::new().await;
+    let round = env
+        .start_round(RoundConfig {
+            empty_round_policy: EmptyRoundPolicy::RollOver,
+            ..env.config()
+        })
+        .await;
+    let successor = env.start_round(env.config()).await;
+    env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+
+    let payer = env.ctx.payer.pubkey();
+    let owner = round.owner.pubkey();
+    let roll_over = instruction::roll_over(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &successor.round,
+        &payer,
+    );
+    let result = env
+        .process(slice::from_ref(&roll_over), &[&round.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+    env.end_round(&round).await;
+
+    let stranger = Keypair::new();
+    let result = env
+        .process(
+            &[instruction::roll_over(
+                &env.program_id,
+                &round.round,
+                &stranger.pubkey(),
+                &env.mint,
+                &successor.round,
+                &payer,
+            )],
+            &[&stranger],
+        )
+        .await;
+    assert_qf_error(result, 0, QFError::OwnerMismatch);
+
+    let later = env
+        .start_round(RoundConfig {
+            start_time: env.now + 100,
+            ..env.config()
+        })
+        .await;
+    let result = env
+        .process(
+            &[instruction::roll_over(
+                &env.program_id,
+                &round.round,
+                &owner,
+                &env.mint,
+                &later.round,
+                &payer,
+            )],
+            &[&round.owner],
+        )
+        .await;
+    assert_qf_error(result, 0, QFError::RoundNotStarted);
+
+    // the pool moves to the successor as a donation of the round owner
+    env.refresh_blockhash().await;
+    env.process(&[roll_over], &[&round.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&env.vault(&round)).await, 0);
+    assert_eq!(env.token_balance(&env.vault(&successor)).await, 1_000);
+    assert_eq!(env.get_round(&round).await.fund, 0);
+    assert_eq!(env.get_round(&successor).await.fund, 1_000);
+    let (to, result) = env.withdraw(&round, &project).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to).await, 0);
+
+    // nobody votes in the successor either, so the owner gets the pool back
+    env.end_round(&successor).await;
+    let refunded = env.create_token_account(&owner, 0).await;
+    let refund_donation = instruction::refund_donation(
+        &env.program_id,
+        &successor.round,
+        &env.mint,
+        &owner,
+        &refunded,
+    );
+    env.process(&[refund_donation], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&refunded).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_empty_multi_token_round() {
+    let mut env = Env::new().await;
+    let issuer = Keypair::new();
+    let round = env
+        .start_round(RoundConfig {
+            identity_issuer: issuer.pubkey(),
+            ..env.config()
+        })
+        .await;
+    let payer = env.ctx.payer.pubkey();
+    let from = env.donate(&round, 1_000).await;
+    let (mint, price_feed) = env.add_mint(&round, PRICE_SCALE).await;
+    let from_b = env.create_token_account_of(&mint, &payer, 500).await;
+    let donate = instruction::donate_in_mint(
+        &env.program_id,
+        &round.round,
+        &from_b,
+        &mint,
+        &payer,
+        &price_feed,
+        500,
+        DECIMALS,
+    );
+    env.process(&[donate], &[]).await.unwrap();
+
+    // the only vote is not matched, so the round is empty
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.attest(&issuer, &voter.wallet.pubkey(), 0)
+        .await
+        .unwrap();
+    env.vote_attested(&round, &project, &voter, &issuer.pubkey(), 100)
+        .await
+        .unwrap();
+    env.end_round(&round).await;
+    let state = env.get_round(&round).await;
+    assert!(payout::refunds_donors(&state));
+    assert_eq!(
+        (state.fund, state.votes, state.donated),
+        (1_500, 100, 1_000)
+    );
+    assert_eq!(env.get_round_mint(&round, &mint).await.donated, 500);
+
+    // the votes are paid out of the votes in the vaults, the donations stay
+    // behind for the refunds
+    let (to, result) = env.withdraw(&round, &project).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to).await, 100);
+    let to_b = env
+        .create_token_account_of(&mint, &project.owner.pubkey(), 0)
+        .await;
+    let withdraw_mint = instruction::withdraw_mint(
+        &env.program_id,
+        &round.round,
+        &mint,
+        &project.project,
+        &project.owner.pubkey(),
+        &to_b,
+        None,
+    );
+    let fund_owner = system_instruction::transfer(&payer, &project.owner.pubkey(), 1_000_000_000);
+    env.process(&[fund_owner, withdraw_mint], &[&project.owner])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&to_b).await, 0);
+
+    for (mint, from, amount) in [(env.mint, from, 1_000), (mint, from_b, 500)] {
+        let refund_donation =
+            instruction::refund_donation(&env.program_id, &round.round, &mint, &payer, &from);
+        env.process(&[refund_donation], &[]).await.unwrap();
+        assert_eq!(env.token_balance(&from).await, amount);
+    }
+
+    let owner = round.owner.pubkey();
+    let to = env.create_token_account(&owner, 0).await;
+    let to_b = env.create_token_account_of(&mint, &owner, 0).await;
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &to,
+        &owner,
+        &[(mint, to_b)],
+    );
+    env.process(&[close_round], &[&round.owner]).await.unwrap();
+    let vault = env.vault(&round);
+    let vault_b = instruction::get_vault_address(&env.program_id, &round.round, &mint);
+    let round_mint = instruction::get_round_mint_address(&env.program_id, &round.round, &mint);
+    for account in [vault, vault_b, round_mint, round.round] {
+        let account = env.ctx.banks_client.get_account(account).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_close_round() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    env.donate(&round, 1_000).await;
+    let mut projects = Vec::new();
+    for amount in [100, 200, 300] {
+        let project = env.register_project(&round).await;
+        let voter = env.create_voter(&project, amount).await;
+        env.vote(&round, &project, &voter, amount).await.unwrap();
+        projects.push(project);
+    }
+    let idle = env.register_project(&round).await;
+    env.end_round(&round).await;
+
+    let owner = round.owner.pubkey();
+    let to = env.create_token_account(&owner, 0).await;
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &to,
+        &owner,
+        &[],
+    );
+    let mut paid = 0;
+    for project in projects.iter() {
+        let result = env
+            .process(slice::from_ref(&close_round), &[&round.owner])
+            .await;
+        assert_qf_error(result, 0, QFError::ProjectNotWithdrawn);
+        env.refresh_blockhash().await;
+        let (to, result) = env.withdraw(&round, project).await;
+        result.unwrap();
+        paid += env.token_balance(&to).await;
+    }
+    assert_eq!(env.get_round(&round).await.withdrawn_count, 3);
+
+    // a project without votes never withdraws, anyone settles it
+    let result = env
+        .process(slice::from_ref(&close_round), &[&round.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::ProjectNotWithdrawn);
+    let distribute = instruction::distribute_to_project(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &idle.project,
+        &to,
+    );
+    env.process(&[distribute], &[]).await.unwrap();
+    assert!(env.get_project(&idle).await.withdraw);
+    env.refresh_blockhash().await;
+
+    let vault = env.vault(&round);
+    let residual = env.token_balance(&vault).await;
+    assert_eq!(residual, 1_600 - paid);
+    assert!(residual > 0);
+    env.process(&[close_round], &[&round.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&to).await, residual);
+    for account in [vault, round.round] {
+        let account = env.ctx.banks_client.get_account(account).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_close_cancelled_round() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    let from = env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+    let cancel_round =
+        instruction::cancel_round(&env.program_id, &round.round, &round.owner.pubkey());
+    env.process(&[cancel_round], &[&round.owner]).await.unwrap();
+
+    let owner = round.owner.pubkey();
+    let to = env.create_token_account(&owner, 0).await;
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &to,
+        &owner,
+        &[],
+    );
+    let result = env
+        .process(slice::from_ref(&close_round), &[&round.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::DonationsNotRefunded);
+    let payer = env.ctx.payer.pubkey();
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &from);
+    env.process(&[refund_donation], &[]).await.unwrap();
+
+    env.refresh_blockhash().await;
+    let result = env
+        .process(slice::from_ref(&close_round), &[&round.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::VotesNotRefunded);
+
+    // voters and projects of a cancelled round close once their votes went back
+    let wallet = voter.wallet.pubkey();
+    let close_voter = instruction::close_voter(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &wallet,
+        &payer,
+    );
+    let close_project = instruction::close_project(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &project.owner.pubkey(),
+        &payer,
+        &[],
+    );
+    let result = env.process(slice::from_ref(&close_voter), &[]).await;
+    assert_qf_error(result, 0, QFError::VotesNotRefunded);
+    let result = env
+        .process(slice::from_ref(&close_project), &[&project.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::VotesNotRefunded);
+    let refund_vote = instruction::refund_vote(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &project.project,
+        &wallet,
+        &voter.token_account,
+    );
+    env.process(&[refund_vote], &[]).await.unwrap();
+    env.refresh_blockhash().await;
+    env.process(&[close_voter], &[]).await.unwrap();
+
+    // the project closes after its round as well
+    env.refresh_blockhash().await;
+    env.process(&[close_round], &[&round.owner]).await.unwrap();
+    env.process(&[close_project], &[&project.owner])
+        .await
+        .unwrap();
+    let voter_address = instruction::get_voter_address(&env.program_id, &project.project, &wallet);
+    for account in [
+        env.vault(&round),
+        round.round,
+        project.project,
+        voter_address,
+    ] {
+        let account = env.ctx.banks_client.get_account(account).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_close_accounts() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+
+    let payer = env.ctx.payer.pubkey();
+    let wallet = voter.wallet.pubkey();
+    assert_eq!(env.get_voter(&project, &wallet).await.payer, payer);
+    assert_eq!(env.get_project(&project).await.payer, payer);
+
+    let owner = project.owner.pubkey();
+    let close_voter = instruction::close_voter(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &wallet,
+        &payer,
+    );
+    let close_project = instruction::close_project(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &owner,
+        &payer,
+        &[],
+    );
+    let result = env.process(slice::from_ref(&close_voter), &[]).await;
+    assert_qf_error(result, 0, QFError::ProjectNotWithdrawn);
+    let result = env
+        .process(slice::from_ref(&close_project), &[&project.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::ProjectNotWithdrawn);
+
+    env.end_round(&round).await;
+    env.withdraw(&round, &project).await.1.unwrap();
+
+    let close = instruction::close_voter(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &wallet,
+        &owner,
+    );
+    let result = env.process(&[close], &[]).await;
+    assert_qf_error(result, 0, QFError::PayerMismatch);
+    let close = instruction::close_project(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &owner,
+        &owner,
+        &[],
+    );
+    let result = env.process(&[close], &[&project.owner]).await;
+    assert_qf_error(result, 0, QFError::PayerMismatch);
+
+    // a closed project still lets its voters close
+    let balance = env.ctx.banks_client.get_balance(payer).await.unwrap();
+    env.refresh_blockhash().await;
+    env.process(&[close_project], &[&project.owner])
+        .await
+        .unwrap();
+    env.process(&[close_voter], &[]).await.unwrap();
+    assert!(env.ctx.banks_client.get_balance(payer).await.unwrap() > balance);
+    let voter_address = instruction::get_voter_address(&env.program_id, &project.project, &wallet);
+    for account in [project.project, voter_address] {
+        let account = env.ctx.banks_client.get_account(account).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_close_donor() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    let from = env.donate(&round, 1_000).await;
+
+    let payer = env.ctx.payer.pubkey();
+    let recipient = Pubkey::new_unique();
+    let close_donor =
+        instruction::close_donor(&env.program_id, &round.round, &payer, &env.mint, &recipient);
+    let result = env.process(slice::from_ref(&close_donor), &[]).await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+    let mut close = close_donor.clone();
+    close.accounts[2].pubkey = round.owner.pubkey();
+    let result = env.process(&[close], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::DonorMismatch);
+
+    // a donor of a cancelled round closes once its donation went back
+    let cancel_round =
+        instruction::cancel_round(&env.program_id, &round.round, &round.owner.pubkey());
+    env.process(&[cancel_round], &[&round.owner]).await.unwrap();
+    env.refresh_blockhash().await;
+    let result = env.process(slice::from_ref(&close_donor), &[]).await;
+    assert_qf_error(result, 0, QFError::DonationsNotRefunded);
+    let refund_donation =
+        instruction::refund_donation(&env.program_id, &round.round, &env.mint, &payer, &from);
+    env.process(&[refund_donation], &[]).await.unwrap();
+    env.refresh_blockhash().await;
+    env.process(&[close_donor], &[]).await.unwrap();
+    let donor = instruction::get_donor_address(&env.program_id, &round.round, &payer, &env.mint);
+    let account = env.ctx.banks_client.get_account(donor).await.unwrap();
+    assert!(account.is_none());
+    assert!(env.ctx.banks_client.get_balance(recipient).await.unwrap() > 0);
+
+    // a donation matched in a finished round is never refunded, its donor
+    // closes after the round
+    let round = env.start_round(env.config()).await;
+    env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+    env.end_round(&round).await;
+    env.withdraw(&round, &project).await.1.unwrap();
+
+    let close_donor =
+        instruction::close_donor(&env.program_id, &round.round, &payer, &env.mint, &recipient);
+    let result = env.process(slice::from_ref(&close_donor), &[]).await;
+    assert_qf_error(result, 0, QFError::DonationsNotRefunded);
+    let owner = round.owner.pubkey();
+    let to = env.create_token_account(&owner, 0).await;
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &to,
+        &owner,
+        &[],
+    );
+    env.process(&[close_round], &[&round.owner]).await.unwrap();
+    env.refresh_blockhash().await;
+    env.process(&[close_donor], &[]).await.unwrap();
+    let donor = instruction::get_donor_address(&env.program_id, &round.round, &payer, &env.mint);
+    let account = env.ctx.banks_client.get_account(donor).await.unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn test_close_project_multi_token_round() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    env.donate(&round, 1_000).await;
+    let (mint, price_feed) = env.add_mint(&round, PRICE_SCALE).await;
+    let payer = env.ctx.payer.pubkey();
+    let from = env.create_token_account_of(&mint, &payer, 500).await;
+    let donate = instruction::donate_in_mint(
+        &env.program_id,
+        &round.round,
+        &from,
+        &mint,
+        &payer,
+        &price_feed,
+        500,
+        DECIMALS,
+    );
+    env.process(&[donate], &[]).await.unwrap();
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+    env.end_round(&round).await;
+    env.withdraw(&round, &project).await.1.unwrap();
+
+    // the share in the vault of the other mint is withdrawn first
+    let owner = project.owner.pubkey();
+    let close_project = instruction::close_project(
+        &env.program_id,
+        &round.round,
+        &project.project,
+        &owner,
+        &payer,
+        &[mint],
+    );
+    let result = env
+        .process(slice::from_ref(&close_project), &[&project.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::ProjectNotWithdrawn);
+    let to_b = env.create_token_account_of(&mint, &owner, 0).await;
+    let withdraw_mint = instruction::withdraw_mint(
+        &env.program_id,
+        &round.round,
+        &mint,
+        &project.project,
+        &owner,
+        &to_b,
+        None,
+    );
+    let fund_owner = system_instruction::transfer(&payer, &owner, 1_000_000_000);
+    env.process(&[fund_owner, withdraw_mint], &[&project.owner])
+        .await
+        .unwrap();
+    env.refresh_blockhash().await;
+    env.process(&[close_project], &[&project.owner])
+        .await
+        .unwrap();
+
+    // the round closes with the project gone
+    let round_owner = round.owner.pubkey();
+    let to = env.create_token_account(&round_owner, 0).await;
+    let to_b = env.create_token_account_of(&mint, &round_owner, 0).await;
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &round_owner,
+        &env.mint,
+        &to,
+        &round_owner,
+        &[(mint, to_b)],
+    );
+    env.process(&[close_round], &[&round.owner]).await.unwrap();
+    for account in [project.project, round.round] {
+        let account = env.ctx.banks_client.get_account(account).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_distribute_to_project() {
+    let mut env = Env::new().await;
+    let round = env.start_round(env.config()).await;
+    env.donate(&round, 1_000).await;
+    let (mint, price_feed) = env.add_mint(&round, 2 * PRICE_SCALE).await;
+    let payer = env.ctx.payer.pubkey();
+    let from = env.create_token_account_of(&mint, &payer, 50).await;
+    let donate = instruction::donate_in_mint(
+        &env.program_id,
+        &round.round,
+        &from,
+        &mint,
+        &payer,
+        &price_feed,
+        50,
+        DECIMALS,
+    );
+    env.process(&[donate], &[]).await.unwrap();
+
+    let payout_owner = Pubkey::new_unique();
+    let payout = env.create_token_account(&payout_owner, 0).await;
+    let metadata = ProjectMetadata {
+        payout,
+        ..ProjectMetadata::default()
+    };
+    let (project, result) = env.try_register_project_with(&round, &metadata).await;
+    result.unwrap();
+    let unset = env.register_project(&round).await;
+    for (project, amount) in [(&project, 100), (&unset, 400)] {
+        let voter = env.create_voter(project, amount).await;
+        env.vote(&round, project, &voter, amount).await.unwrap();
+    }
+
+    let distribute = instruction::distribute_to_project(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &project.project,
+        &payout,
+    );
+    let result = env.process(slice::from_ref(&distribute), &[]).await;
+    assert_qf_error(result, 0, QFError::RoundStatusError);
+    env.end_round(&round).await;
+
+    let other = env.create_token_account(&project.owner.pubkey(), 0).await;
+    let result = env
+        .process(
+            &[instruction::distribute_to_project(
+                &env.program_id,
+                &round.round,
+                &env.mint,
+                &project.project,
+                &other,
+            )],
+            &[],
+        )
+        .await;
+    assert_qf_error(result, 0, QFError::PayoutMismatch);
+    let result = env
+        .process(
+            &[instruction::distribute_to_project(
+                &env.program_id,
+                &round.round,
+                &env.mint,
+                &unset.project,
+                &other,
+            )],
+            &[],
+        )
+        .await;
+    assert_qf_error(result, 0, QFError::PayoutNotSet);
+
+    // anyone cranks the payout to the registered account, only once
+    let state = env.get_round(&round).await;
+    let amount = payout::project_payout(&state, &env.get_project(&project).await, 1_100 / 5)
+        .unwrap()
+        .amount;
+    env.refresh_blockhash().await;
+    env.process(slice::from_ref(&distribute), &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(&payout).await,
+        payout::vault_share(&state, amount, 1_500, 1_000).unwrap()
+    );
+    assert!(env.get_project(&project).await.withdraw);
+    env.refresh_blockhash().await;
+    let result = env.process(&[distribute], &[]).await;
+    assert_qf_error(result, 0, QFError::ProjectAlreadyWithdraw);
+
+    // shares of other mints only go to the owner of the payout account
+    let to = env
+        .create_token_account_of(&mint, &project.owner.pubkey(), 0)
+        .await;
+    let withdraw_mint = instruction::withdraw_mint(
+        &env.program_id,
+        &round.round,
+        &mint,
+        &project.project,
+        &project.owner.pubkey(),
+        &to,
+        Some(&payout),
+    );
+    let result = env.process(&[withdraw_mint], &[&project.owner]).await;
+    assert_qf_error(result, 0, QFError::PayoutMismatch);
+    let to = env.create_token_account_of(&mint, &payout_owner, 0).await;
+    let distribute_mint = instruction::distribute_to_project_mint(
+        &env.program_id,
+        &round.round,
+        &mint,
+        &project.project,
+        &to,
+        &payout,
+        &payer,
+    );
+    env.process(&[distribute_mint], &[]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&to).await,
+        payout::vault_share(&state, amount, 50, 50).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_vesting() {
+    let mut env = Env::new().await;
+    let mut config = env.config();
+    config.vesting_cliff = 100;
+    config.vesting_duration = 400;
+    let round = env.start_round(config).await;
+    env.donate(&round, 1_000).await;
+    let a = env.register_project(&round).await;
+    let b = env.register_project(&round).await;
+    let c = env.register_project(&round).await;
+    for (project, amount) in [(&a, 100), (&b, 400)] {
+        let voter = env.create_voter(project, amount).await;
+        env.vote(&round, project, &voter, amount).await.unwrap();
+    }
+    env.end_round(&round).await;
+    let finished_time = env.get_round(&round).await.finished_time;
+
+    // a withdrawal paying nothing still keeps the round from being cancelled
+    let (_, result) = env.withdraw(&round, &c).await;
+    result.unwrap();
+    assert_eq!(env.get_round(&round).await.paid_out, 0);
+    let cancel_round =
+        instruction::cancel_round(&env.program_id, &round.round, &round.owner.pubkey());
+    let result = env.process(&[cancel_round], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::RoundAlreadyPaidOut);
+
+    // contributions are paid right away, matching funds go into the escrow
+    let (to_a, result) = env.withdraw(&round, &a).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to_a).await, 100);
+    assert_eq!(env.get_project(&a).await.escrowed, 200);
+    let (to_b, result) = env.withdraw(&round, &b).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to_b).await, 400);
+    assert_eq!(env.get_round(&round).await.escrowed, 1_000);
+
+    let claim_a = instruction::claim_vested(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &a.project,
+        &a.owner.pubkey(),
+        &to_a,
+    );
+    for (time, claimed) in [(finished_time + 99, 0), (finished_time + 200, 100)] {
+        env.set_time(time).await;
+        env.refresh_blockhash().await;
+        env.process(slice::from_ref(&claim_a), &[&a.owner])
+            .await
+            .unwrap();
+        assert_eq!(env.token_balance(&to_a).await, 100 + claimed);
+    }
+
+    // the round owner claws back what has not vested yet
+    env.set_time(finished_time + 300).await;
+    let owner = round.owner.pubkey();
+    let clawed = env.create_token_account(&owner, 0).await;
+    let claw_back = instruction::claw_back(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &b.project,
+        &clawed,
+    );
+    env.process(slice::from_ref(&claw_back), &[&round.owner])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&clawed).await, 200);
+    env.refresh_blockhash().await;
+    let result = env.process(&[claw_back], &[&round.owner]).await;
+    assert_qf_error(result, 0, QFError::ProjectFlagged);
+    let claim_b = instruction::claim_vested(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &b.project,
+        &b.owner.pubkey(),
+        &to_b,
+    );
+    env.process(&[claim_b], &[&b.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&to_b).await, 400 + 600);
+
+    let close_round = instruction::close_round(
+        &env.program_id,
+        &round.round,
+        &owner,
+        &env.mint,
+        &clawed,
+        &owner,
+        &[],
+    );
+    let result = env
+        .process(slice::from_ref(&close_round), &[&round.owner])
+        .await;
+    assert_qf_error(result, 0, QFError::EscrowOutstanding);
+
+    env.set_time(finished_time + 400).await;
+    env.refresh_blockhash().await;
+    env.process(&[claim_a], &[&a.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&to_a).await, 300);
+    assert_eq!(env.get_round(&round).await.escrowed, 0);
+    env.refresh_blockhash().await;
+    env.process(&[close_round], &[&round.owner]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_vesting_ended_early() {
+    let mut env = Env::new().await;
+    let mut config = env.config();
+    config.end_time = env.now + 10_000;
+    config.registration_end_time = env.now + 10_000;
+    config.vesting_cliff = 100;
+    config.vesting_duration = 400;
+    let round = env.start_round(config).await;
+    env.donate(&round, 1_000).await;
+    let project = env.register_project(&round).await;
+    let voter = env.create_voter(&project, 100).await;
+    env.vote(&round, &project, &voter, 100).await.unwrap();
+
+    // vesting starts when the owner ends the round, not at its end time
+    env.end_round(&round).await;
+    let round_state = env.get_round(&round).await;
+    assert!(round_state.finished_time + 400 < round_state.end_time);
+    let (to, result) = env.withdraw(&round, &project).await;
+    result.unwrap();
+    assert_eq!(env.token_balance(&to).await, 100);
+    assert_eq!(env.get_project(&project).await.escrowed, 1_000);
+
+    let claim = instruction::claim_vested(
+        &env.program_id,
+        &round.round,
+        &env.mint,
+        &project.project,
+        &project.owner.pubkey(),
+        &to,
+    );
+    env.set_time(round_state.finished_time + 200).await;
+    env.process(&[claim], &[&project.owner]).await.unwrap();
+    assert_eq!(env.token_balance(&to).await, 100 + 500);
+}
@@ -0,0 +1,26 @@
+use qf::error::QFError;
+use solana_client::client_error::ClientError;
+use solana_program::program_error::ProgramError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum QFClientError {
+    #[error("rpc error: {0}")]
+    Rpc(Box<ClientError>),
+
+    #[error("account {0} is not owned by the qf program")]
+    IncorrectProgramId(String),
+
+    #[error("failed to decode account: {0}")]
+    Decode(#[from] ProgramError),
+
+    #[error("failed to simulate payouts: {0}")]
+    Payout(#[from] QFError),
+}
+impl From<ClientError> for QFClientError {
+    fn from(e: ClientError) -> Self {
+        QFClientError::Rpc(Box::new(e))
+    }
+}
+
+pub type QFClientResult<T> = Result<T, QFClientError>;
@@ -0,0 +1,260 @@
+pub mod error;
+
+use crate::error::{QFClientError, QFClientResult};
+use qf::{
+    payout::{self, Payout},
+    state::{Attestation, Donor, PriceFeed, Project, ProjectMetadata, Round, RoundMint, Voter},
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_program::{
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+
+/// Offset of `Project.round` in a project account, the round is the first
+/// field of a project
+const PROJECT_ROUND_OFFSET: usize = 0;
+
+/// Payout of a project if the round ended now
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProjectedPayout {
+    pub project: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+/// Reads and decodes the accounts of a QF program
+pub struct QFClient {
+    rpc_client: RpcClient,
+    program_id: Pubkey,
+}
+
+impl QFClient {
+    pub fn new(rpc_client: RpcClient, program_id: Pubkey) -> Self {
+        Self {
+            rpc_client,
+            program_id,
+        }
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub fn get_round(&self, round: &Pubkey) -> QFClientResult<Round> {
+        self.get_packed(round)
+    }
+
+    pub fn get_project(&self, project: &Pubkey) -> QFClientResult<Project> {
+        Ok(Project::unpack_account(
+            &self.get_program_account_data(project)?,
+        )?)
+    }
+
+    /// Fetches the metadata stored after the project in its account
+    pub fn get_project_metadata(&self, project: &Pubkey) -> QFClientResult<ProjectMetadata> {
+        let data = self.get_program_account_data(project)?;
+        Project::unpack_account(&data)?;
+        Ok(ProjectMetadata::unpack(&data[Project::LEN..])?)
+    }
+
+    pub fn get_voter(&self, voter: &Pubkey) -> QFClientResult<Voter> {
+        self.get_packed(voter)
+    }
+
+    pub fn get_donor(&self, donor: &Pubkey) -> QFClientResult<Donor> {
+        self.get_packed(donor)
+    }
+
+    pub fn get_price_feed(&self, price_feed: &Pubkey) -> QFClientResult<PriceFeed> {
+        self.get_packed(price_feed)
+    }
+
+    pub fn get_round_mint(&self, round_mint: &Pubkey) -> QFClientResult<RoundMint> {
+        self.get_packed(round_mint)
+    }
+
+    pub fn get_attestation(&self, attestation: &Pubkey) -> QFClientResult<Attestation> {
+        self.get_packed(attestation)
+    }
+
+    /// Lists every project registered to the round
+    pub fn get_projects(&self, round: &Pubkey) -> QFClientResult<Vec<(Pubkey, Project)>> {
+        let accounts = self.rpc_client.get_program_accounts_with_config(
+            &self.program_id,
+            RpcProgramAccountsConfig {
+                // project accounts vary in size with their metadata
+                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    PROJECT_ROUND_OFFSET,
+                    &round.to_bytes(),
+                ))]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            },
+        )?;
+        accounts
+            .into_iter()
+            .map(|(pubkey, account)| Ok((pubkey, Project::unpack_account(&account.data)?)))
+            .collect()
+    }
+
+    /// Simulates what every project of the round would receive if the round
+    /// ended now, see [`payout::simulate_payouts`]
+    pub fn get_projected_payouts(&self, round: &Pubkey) -> QFClientResult<Vec<ProjectedPayout>> {
+        let (pubkeys, projects): (Vec<Pubkey>, Vec<Project>) =
+            self.get_projects(round)?.into_iter().unzip();
+        let round = self.get_round(round)?;
+        Ok(pubkeys
+            .into_iter()
+            .zip(payout::simulate_payouts(&round, &projects)?)
+            .map(|(project, Payout { amount, fee })| ProjectedPayout {
+                project,
+                amount,
+                fee,
+            })
+            .collect())
+    }
+
+    fn get_packed<T: Pack + IsInitialized>(&self, pubkey: &Pubkey) -> QFClientResult<T> {
+        Ok(T::unpack(&self.get_program_account_data(pubkey)?)?)
+    }
+
+    fn get_program_account_data(&self, pubkey: &Pubkey) -> QFClientResult<Vec<u8>> {
+        let account = self.rpc_client.get_account(pubkey)?;
+        if account.owner != self.program_id {
+            return Err(QFClientError::IncorrectProgramId(pubkey.to_string()));
+        }
+        Ok(account.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qf::state::{ProjectStatus, RoundStatus};
+    use serde_json::{json, Value};
+    use solana_account_decoder::UiAccount;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_sdk::account::Account;
+    use spl_math::uint::U256;
+    use std::collections::HashMap;
+
+    fn ui_account(program_id: &Pubkey, pubkey: &Pubkey, data: Vec<u8>) -> Value {
+        let account = Account {
+            lamports: 1_000_000,
+            data,
+            owner: *program_id,
+            executable: false,
+            rent_epoch: 0,
+        };
+        json!(UiAccount::encode(
+            pubkey,
+            &account,
+            UiAccountEncoding::Base64,
+            None,
+            None
+        ))
+    }
+
+    #[test]
+    fn test_project_round_offset() {
+        let round = Pubkey::new_unique();
+        let project = Project {
+            round,
+            owner: Pubkey::new_unique(),
+            votes: u64::MAX,
+            area: U256::MAX,
+            payer: Pubkey::new_unique(),
+            ..Project::default()
+        };
+        let mut data = vec![0; Project::LEN];
+        Project::pack(project, &mut data).unwrap();
+        assert_eq!(
+            data[PROJECT_ROUND_OFFSET..PROJECT_ROUND_OFFSET + 32],
+            round.to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_get_projected_payouts() {
+        let program_id = Pubkey::new_unique();
+        let round_key = Pubkey::new_unique();
+        let round = Round {
+            status: RoundStatus::Ongoing,
+            fund: 1_000,
+            fee_bps: 500,
+            area: U256::from(900 + 400 + 100),
+            project_count: 3,
+            votes: 60,
+            deposited: 1_060,
+            ..Round::default()
+        };
+        let projects: Vec<(Pubkey, Project)> = [(900, 30), (400, 20), (100, 10)]
+            .iter()
+            .enumerate()
+            .map(|(index, &(area, votes))| {
+                let project = Project {
+                    round: round_key,
+                    votes,
+                    area: U256::from(area),
+                    index: index as u64,
+                    status: ProjectStatus::Approved,
+                    ..Project::default()
+                };
+                (Pubkey::new_unique(), project)
+            })
+            .collect();
+
+        let mut round_data = vec![0; Round::LEN];
+        Round::pack(round, &mut round_data).unwrap();
+        let project_accounts: Vec<Value> = projects
+            .iter()
+            .map(|(pubkey, project)| {
+                let mut data = vec![0; Project::account_len(&ProjectMetadata::default())];
+                Project::pack_account(*project, &mut data).unwrap();
+                json!({
+                    "pubkey": pubkey.to_string(),
+                    "account": ui_account(&program_id, pubkey, data),
+                })
+            })
+            .collect();
+        let mut mocks = HashMap::new();
+        mocks.insert(RpcRequest::GetProgramAccounts, json!(project_accounts));
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            json!({
+                "context": {"slot": 1},
+                "value": ui_account(&program_id, &round_key, round_data),
+            }),
+        );
+        let client = QFClient::new(
+            RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks),
+            program_id,
+        );
+
+        let (pubkeys, projects): (Vec<Pubkey>, Vec<Project>) = projects.into_iter().unzip();
+        let expected: Vec<ProjectedPayout> = pubkeys
+            .into_iter()
+            .zip(payout::simulate_payouts(&round, &projects).unwrap())
+            .map(|(project, Payout { amount, fee })| ProjectedPayout {
+                project,
+                amount,
+                fee,
+            })
+            .collect();
+        assert!(expected.iter().all(|payout| payout.amount > 0));
+        assert_eq!(client.get_projected_payouts(&round_key).unwrap(), expected);
+    }
+}